@@ -1,4 +1,8 @@
 from .phenolrs import *  # noqa: F403
+from .aio import (  # noqa: F401
+    graph_to_networkx_format_async,
+    graph_to_numpy_format_async,
+)
 
 
 __doc__ = phenolrs.__doc__  # type: ignore[name-defined]  # noqa: F405
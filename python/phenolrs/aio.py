@@ -0,0 +1,49 @@
+import asyncio
+from typing import Any
+
+from phenolrs import (
+    CancellationToken,
+    LoadTask,
+    start_graph_to_networkx_format,
+    start_graph_to_numpy_format,
+)
+
+
+async def _wait_for(task: LoadTask) -> Any:
+    # Waiting in an executor thread keeps the runtime's threads from calling
+    # into Python, and asyncio.run joins it before the interpreter shuts down
+    loop = asyncio.get_running_loop()
+    try:
+        await loop.run_in_executor(None, task.wait)
+    except asyncio.CancelledError:
+        task.cancel()
+        raise
+    return task.result()
+
+
+async def graph_to_numpy_format_async(
+    request: dict[str, Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: list[str] | None = None,
+    edge_features: bool = False,
+) -> Any:
+    """Awaitable variant of `graph_to_numpy_format`."""
+    return await _wait_for(
+        start_graph_to_numpy_format(
+            request, cancel_token, compressed_formats, edge_features
+        )
+    )
+
+
+async def graph_to_networkx_format_async(
+    request: dict[str, Any],
+    graph_config: dict[str, Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: list[str] | None = None,
+) -> Any:
+    """Awaitable variant of `graph_to_networkx_format`."""
+    return await _wait_for(
+        start_graph_to_networkx_format(
+            request, graph_config, cancel_token, compressed_formats
+        )
+    )
@@ -35,6 +35,8 @@ class NetworkXLoader:
         is_directed: bool = True,
         is_multigraph: bool = True,
         symmetrize_edges_if_directed: bool = False,
+        graph_name: str | None = None,
+        cache_dir: str | None = None,
     ) -> Tuple[
         NodeDict,
         GraphAdjDict | DiGraphAdjDict | MultiGraphAdjDict | MultiDiGraphAdjDict,
@@ -44,19 +46,26 @@ class NetworkXLoader:
         ArangoIDtoIndex,
         EdgeValuesDict,
     ]:
-        if "vertexCollections" not in metagraph:
-            raise PhenolError("vertexCollections not found in metagraph")
-
-        if "edgeCollections" not in metagraph:
-            raise PhenolError("edgeCollections not found in metagraph")
-
-        if len(metagraph["vertexCollections"]) + len(metagraph["edgeCollections"]) == 0:
-            m = "vertexCollections and edgeCollections cannot both be empty"
-            raise PhenolError(m)
-
-        if len(metagraph["edgeCollections"]) == 0 and (load_adj_dict or load_coo):
-            m = "edgeCollections must be non-empty if **load_adj_dict** or **load_coo** is True"  # noqa
-            raise PhenolError(m)
+        # With graph_name, the collections of the named graph are loaded and
+        # the metagraph only lists those whose attributes are needed
+        if graph_name is None:
+            if "vertexCollections" not in metagraph:
+                raise PhenolError("vertexCollections not found in metagraph")
+
+            if "edgeCollections" not in metagraph:
+                raise PhenolError("edgeCollections not found in metagraph")
+
+            if (
+                len(metagraph["vertexCollections"]) + len(metagraph["edgeCollections"])
+                == 0
+            ):
+                m = "vertexCollections and edgeCollections cannot both be empty"
+                raise PhenolError(m)
+
+            if len(metagraph["edgeCollections"]) == 0 and (load_adj_dict or load_coo):
+                m = "edgeCollections must be non-empty if **load_adj_dict** or **load_coo** is True"  # noqa
+                raise PhenolError(m)
+        metagraph = {"vertexCollections": {}, "edgeCollections": {}, **metagraph}
 
         if load_all_vertex_attributes and any(
             [len(entries) > 0 for entries in metagraph["vertexCollections"].values()]
@@ -115,6 +124,19 @@ class NetworkXLoader:
             for e_col_name, entries in metagraph["edgeCollections"].items()
         ]
 
+        request: dict[str, Any] = {
+            "vertex_collections": vertex_collections,
+            "edge_collections": edge_collections,
+            "database_config": db_config_options,
+            "load_config": load_config_options,
+        }
+        if graph_name:
+            request["graph_name"] = graph_name
+        # Reuses the result of an identical load while the collections are
+        # unchanged
+        if cache_dir:
+            request["cache_dir"] = cache_dir
+
         (
             node_dict,
             adj_dict,
@@ -124,12 +146,7 @@ class NetworkXLoader:
             id_to_index_map,
             edge_values,
         ) = graph_to_networkx_format(
-            request={
-                "vertex_collections": vertex_collections,
-                "edge_collections": edge_collections,
-                "database_config": db_config_options,
-                "load_config": load_config_options,
-            },
+            request=request,
             graph_config=graph_config,  # TODO Anthony: Move into request
         )
 
@@ -1,4 +1,4 @@
-from typing import Any, Tuple
+from typing import Any, Literal, Tuple, overload
 
 from phenolrs import PhenolError, graph_to_numpy_format
 
@@ -8,10 +8,62 @@ from .typings import (
     ArangoCollectionToIndexToArangoKey,
     ArangoCollectionToNodeFeatures,
     COOByEdgeType,
+    EdgeFeaturesByEdgeType,
 )
 
+NumpyResult = Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    ArangoCollectionSourceToOutput,
+]
+NumpyResultWithEdgeFeatures = Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    ArangoCollectionSourceToOutput,
+    EdgeFeaturesByEdgeType,
+]
+
 
 class NumpyLoader:
+    @overload
+    @staticmethod
+    def load_graph_to_numpy(
+        database: str,
+        metagraph: dict[str, Any],
+        hosts: list[str],
+        user_jwt: str | None = None,
+        username: str | None = None,
+        password: str | None = None,
+        tls_cert: Any | None = None,
+        parallelism: int | None = None,
+        batch_size: int | None = None,
+        cache_dir: str | None = None,
+        edge_features: Literal[False] = False,
+        graph_name: str | None = None,
+    ) -> NumpyResult: ...
+
+    @overload
+    @staticmethod
+    def load_graph_to_numpy(
+        database: str,
+        metagraph: dict[str, Any],
+        hosts: list[str],
+        user_jwt: str | None = None,
+        username: str | None = None,
+        password: str | None = None,
+        tls_cert: Any | None = None,
+        parallelism: int | None = None,
+        batch_size: int | None = None,
+        cache_dir: str | None = None,
+        *,
+        edge_features: Literal[True],
+        graph_name: str | None = None,
+    ) -> NumpyResultWithEdgeFeatures: ...
+
     @staticmethod
     def load_graph_to_numpy(
         database: str,
@@ -23,13 +75,14 @@ class NumpyLoader:
         tls_cert: Any | None = None,
         parallelism: int | None = None,
         batch_size: int | None = None,
-    ) -> Tuple[
-        ArangoCollectionToNodeFeatures,
-        COOByEdgeType,
-        ArangoCollectionToArangoKeyToIndex,
-        ArangoCollectionToIndexToArangoKey,
-        ArangoCollectionSourceToOutput,
-    ]:
+        cache_dir: str | None = None,
+        edge_features: bool = False,
+        graph_name: str | None = None,
+    ) -> NumpyResult | NumpyResultWithEdgeFeatures:
+        # With edge_features, the numeric attributes of the edges in
+        # edgeCollections are returned as well, see graph_to_numpy_format.
+        # With graph_name, the collections of the named graph are loaded and
+        # the metagraph only lists those whose attributes are needed.
         # TODO: replace with pydantic validation
         db_config_options: dict[str, Any] = {
             "endpoints": hosts,
@@ -51,8 +104,9 @@ class NumpyLoader:
         if tls_cert:
             db_config_options["tls_cert"] = tls_cert
 
-        if "vertexCollections" not in metagraph:
+        if graph_name is None and "vertexCollections" not in metagraph:
             raise PhenolError("vertexCollections not found in metagraph")
+        metagraph = {"vertexCollections": {}, **metagraph}
 
         # Address the possibility of having something like this:
         # "USER": {"x": {"features": None}}
@@ -91,19 +145,42 @@ class NumpyLoader:
                 for e_col_name, entries in metagraph["edgeCollections"].items()
             ]
 
+        request: dict[str, Any] = {
+            "vertex_collections": vertex_collections,
+            "edge_collections": edge_collections,
+            "database_config": db_config_options,
+            "load_config": load_config_options,
+        }
+        # Reuses the result of an identical load while the collections are
+        # unchanged
+        if cache_dir:
+            request["cache_dir"] = cache_dir
+        if graph_name:
+            request["graph_name"] = graph_name
+
+        if edge_features:
+            (
+                features_by_col,
+                coo_map,
+                col_to_adb_key_to_ind,
+                col_to_ind_to_adb_key,
+                edge_features_by_type,
+            ) = graph_to_numpy_format(request, edge_features=True)
+            return (
+                features_by_col,
+                coo_map,
+                col_to_adb_key_to_ind,
+                col_to_ind_to_adb_key,
+                vertex_cols_source_to_output,
+                edge_features_by_type,
+            )
+
         (
             features_by_col,
             coo_map,
             col_to_adb_key_to_ind,
             col_to_ind_to_adb_key,
-        ) = graph_to_numpy_format(
-            {
-                "vertex_collections": vertex_collections,
-                "edge_collections": edge_collections,
-                "database_config": db_config_options,
-                "load_config": load_config_options,
-            }
-        )
+        ) = graph_to_numpy_format(request)
 
         return (
             features_by_col,
@@ -7,6 +7,14 @@ EdgeType = Tuple[str, str, str]
 
 ArangoCollectionToNodeFeatures = dict[str, dict[str, npt.NDArray[np.float64]]]
 COOByEdgeType = dict[EdgeType, npt.NDArray[np.float64]]
+# One row per COO column of the edge type, NaN where an edge has no value
+EdgeFeaturesByEdgeType = dict[EdgeType, dict[str, npt.NDArray[np.float64]]]
 ArangoCollectionToArangoKeyToIndex = dict[str, dict[str, int]]
 ArangoCollectionToIndexToArangoKey = dict[str, dict[int, str]]
 ArangoCollectionSourceToOutput = dict[str, dict[str, str]]
+# indptr, indices and the permutation into the COO order, by "csr" or "csc"
+CompressedAdjacency = dict[
+    str,
+    Tuple[npt.NDArray[np.int64], npt.NDArray[np.int64], npt.NDArray[np.int64]],
+]
+CompressedByEdgeType = dict[EdgeType, CompressedAdjacency]
@@ -1,3 +1,4 @@
+import os
 import typing
 
 import numpy as np
@@ -20,16 +21,153 @@ from .numpy.typings import (
     ArangoCollectionToIndexToArangoKey,
     ArangoCollectionToNodeFeatures,
     COOByEdgeType,
+    CompressedAdjacency,
+    CompressedByEdgeType,
+    EdgeFeaturesByEdgeType,
 )
 
-def graph_to_numpy_format(request: dict[str, typing.Any]) -> typing.Tuple[
+# Keys of `request["database_config"]` and of the configuration of `Session`:
+#   endpoints, database, username, password, jwt_token, tls_cert
+#   connect_timeout, request_timeout: in seconds, for every request of a load,
+#     whether it reads collections or runs AQL queries. A retried request gets
+#     the full timeout again, `load_config["load_deadline"]` bounds the load.
+#
+# `request["graph_name"]` loads the collections of a named graph. Collections
+# listed in vertex_collections or edge_collections keep their fields, the
+# others are loaded without any. Cannot be combined with queries or a
+# file_source.
+
+class CancellationToken:
+    def __init__(self) -> None: ...
+    def cancel(self) -> None: ...
+    def is_cancelled(self) -> bool: ...
+
+class LoadTask:
+    def done(self) -> bool: ...
+    def cancel(self) -> None: ...
+    def wait(self, timeout: float | None = None) -> bool: ...
+    def result(self) -> typing.Any: ...
+
+class ArrowTable:
+    num_rows: int
+    column_names: list[str]
+    def __len__(self) -> int: ...
+    def __arrow_c_schema__(self) -> typing.Any: ...
+    def __arrow_c_array__(
+        self, requested_schema: typing.Any | None = None
+    ) -> typing.Tuple[typing.Any, typing.Any]: ...
+    def __arrow_c_stream__(
+        self, requested_schema: typing.Any | None = None
+    ) -> typing.Any: ...
+
+class Session:
+    def __init__(
+        self,
+        database_config: dict[str, typing.Any],
+        retry: dict[str, typing.Any] | None = None,
+    ) -> None: ...
+    def load_numpy(
+        self,
+        request: dict[str, typing.Any],
+        cancel_token: CancellationToken | None = None,
+    ) -> typing.Tuple[
+        ArangoCollectionToNodeFeatures,
+        COOByEdgeType,
+        ArangoCollectionToArangoKeyToIndex,
+        ArangoCollectionToIndexToArangoKey,
+    ]: ...
+    def load_networkx(
+        self,
+        request: dict[str, typing.Any],
+        graph_config: dict[str, typing.Any],
+        cancel_token: CancellationToken | None = None,
+    ) -> typing.Tuple[
+        NodeDict,
+        GraphAdjDict | DiGraphAdjDict | MultiGraphAdjDict | MultiDiGraphAdjDict,
+        SrcIndices,
+        DstIndices,
+        EdgeIndices,
+        ArangoIDtoIndex,
+        EdgeValuesDict,
+    ]: ...
+
+@typing.overload
+def graph_to_numpy_format(
+    request: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: None = None,
+    edge_features: typing.Literal[False] = False,
+) -> typing.Tuple[
     ArangoCollectionToNodeFeatures,
     COOByEdgeType,
     ArangoCollectionToArangoKeyToIndex,
     ArangoCollectionToIndexToArangoKey,
 ]: ...
+@typing.overload
+def graph_to_numpy_format(
+    request: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    *,
+    compressed_formats: list[str],
+    edge_features: typing.Literal[False] = False,
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    CompressedByEdgeType,
+]: ...
+@typing.overload
+def graph_to_numpy_format(
+    request: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: None = None,
+    *,
+    edge_features: typing.Literal[True],
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    EdgeFeaturesByEdgeType,
+]: ...
+@typing.overload
+def graph_to_numpy_format(
+    request: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    *,
+    compressed_formats: list[str],
+    edge_features: typing.Literal[True],
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    EdgeFeaturesByEdgeType,
+    CompressedByEdgeType,
+]: ...
+@typing.overload
+def graph_to_networkx_format(
+    request: dict[str, typing.Any],
+    graph_config: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: None = None,
+) -> typing.Tuple[
+    NodeDict,
+    GraphAdjDict | DiGraphAdjDict | MultiGraphAdjDict | MultiDiGraphAdjDict,
+    SrcIndices,
+    DstIndices,
+    EdgeIndices,
+    ArangoIDtoIndex,
+    EdgeValuesDict,
+]: ...
+@typing.overload
 def graph_to_networkx_format(
-    request: dict[str, typing.Any], graph_config: dict[str, typing.Any]
+    request: dict[str, typing.Any],
+    graph_config: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    *,
+    compressed_formats: list[str],
 ) -> typing.Tuple[
     NodeDict,
     GraphAdjDict | DiGraphAdjDict | MultiGraphAdjDict | MultiDiGraphAdjDict,
@@ -38,6 +176,148 @@ def graph_to_networkx_format(
     EdgeIndices,
     ArangoIDtoIndex,
     EdgeValuesDict,
+    CompressedAdjacency,
+]: ...
+def graph_to_arrow_format(
+    request: dict[str, typing.Any], cancel_token: CancellationToken | None = None
+) -> typing.Tuple[
+    dict[str, ArrowTable], dict[typing.Tuple[str, str, str], ArrowTable]
 ]: ...
+def start_graph_to_numpy_format(
+    request: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: list[str] | None = None,
+    edge_features: bool = False,
+) -> LoadTask: ...
+def start_graph_to_networkx_format(
+    request: dict[str, typing.Any],
+    graph_config: dict[str, typing.Any],
+    cancel_token: CancellationToken | None = None,
+    compressed_formats: list[str] | None = None,
+) -> LoadTask: ...
+def save_numpy_snapshot(
+    path: str | os.PathLike[str],
+    result: (
+        typing.Tuple[
+            ArangoCollectionToNodeFeatures,
+            COOByEdgeType,
+            ArangoCollectionToArangoKeyToIndex,
+            ArangoCollectionToIndexToArangoKey,
+        ]
+        | typing.Tuple[
+            ArangoCollectionToNodeFeatures,
+            COOByEdgeType,
+            ArangoCollectionToArangoKeyToIndex,
+            ArangoCollectionToIndexToArangoKey,
+            EdgeFeaturesByEdgeType,
+        ]
+        | typing.Tuple[
+            ArangoCollectionToNodeFeatures,
+            COOByEdgeType,
+            ArangoCollectionToArangoKeyToIndex,
+            ArangoCollectionToIndexToArangoKey,
+            CompressedByEdgeType,
+        ]
+        | typing.Tuple[
+            ArangoCollectionToNodeFeatures,
+            COOByEdgeType,
+            ArangoCollectionToArangoKeyToIndex,
+            ArangoCollectionToIndexToArangoKey,
+            EdgeFeaturesByEdgeType,
+            CompressedByEdgeType,
+        ]
+    ),
+) -> None: ...
+@typing.overload
+def load_numpy_snapshot(
+    path: str | os.PathLike[str],
+    edge_features: typing.Literal[False] = False,
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+]: ...
+@typing.overload
+def load_numpy_snapshot(
+    path: str | os.PathLike[str],
+    edge_features: typing.Literal[True],
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    EdgeFeaturesByEdgeType,
+]: ...
+def save_networkx_snapshot(
+    path: str | os.PathLike[str],
+    result: typing.Tuple[
+        NodeDict,
+        GraphAdjDict | DiGraphAdjDict | MultiGraphAdjDict | MultiDiGraphAdjDict,
+        SrcIndices,
+        DstIndices,
+        EdgeIndices,
+        ArangoIDtoIndex,
+        EdgeValuesDict,
+    ],
+    graph_config: dict[str, typing.Any],
+) -> None: ...
+def load_networkx_snapshot(
+    path: str | os.PathLike[str],
+) -> typing.Tuple[
+    NodeDict,
+    GraphAdjDict | DiGraphAdjDict | MultiGraphAdjDict | MultiDiGraphAdjDict,
+    SrcIndices,
+    DstIndices,
+    EdgeIndices,
+    ArangoIDtoIndex,
+    EdgeValuesDict,
+]: ...
+def graph_to_parquet(
+    request: dict[str, typing.Any],
+    path: str | os.PathLike[str],
+    cancel_token: CancellationToken | None = None,
+) -> None: ...
+@typing.overload
+def load_numpy_parquet(
+    path: str | os.PathLike[str],
+    edge_features: typing.Literal[False] = False,
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+]: ...
+@typing.overload
+def load_numpy_parquet(
+    path: str | os.PathLike[str],
+    edge_features: typing.Literal[True],
+) -> typing.Tuple[
+    ArangoCollectionToNodeFeatures,
+    COOByEdgeType,
+    ArangoCollectionToArangoKeyToIndex,
+    ArangoCollectionToIndexToArangoKey,
+    EdgeFeaturesByEdgeType,
+]: ...
+def inspect_graph(
+    request: dict[str, typing.Any], sample_size: int = 1000
+) -> dict[str, typing.Any]: ...
 
 class PhenolError(Exception): ...
+class PhenolCancelledError(PhenolError): ...
+class PhenolConnectionError(PhenolError): ...
+class PhenolAuthenticationError(PhenolError): ...
+
+class PhenolCollectionNotFoundError(PhenolError):
+    collection: str | None
+
+class PhenolSchemaError(PhenolError):
+    collection: str | None
+    document_id: str | None
+    attribute: str | None
+
+class PhenolDuplicateEdgeError(PhenolError):
+    from_id: str
+    to_id: str
+
+class PhenolTimeoutError(PhenolError): ...
@@ -19,6 +19,17 @@ except ImportError:
     TORCH_AVAILABLE = False
 
 
+def _edge_feature_tensor(name: str, values: np.ndarray) -> "torch.Tensor":
+    result = torch.from_numpy(values.astype(np.float64))
+    # PyG takes one weight per edge rather than a column of them
+    if name == "edge_weight":
+        if result.shape[1] != 1:
+            m = f"edge_weight must be a single number per edge, found {result.shape[1]}"
+            raise PhenolError(m)
+        result = result.reshape(-1)
+    return result
+
+
 class PygLoader:
     @staticmethod
     def load_into_pyg_data(
@@ -31,6 +42,7 @@ class PygLoader:
         tls_cert: Any | None = None,
         parallelism: int | None = None,
         batch_size: int | None = None,
+        graph_name: str | None = None,
     ) -> Tuple[
         "Data", ArangoCollectionToArangoKeyToIndex, ArangoCollectionToIndexToArangoKey
     ]:
@@ -64,6 +76,7 @@ class PygLoader:
             col_to_adb_key_to_ind,
             col_to_ind_to_adb_key,
             vertex_cols_source_to_output,
+            edge_features_by_type,
         ) = NumpyLoader.load_graph_to_numpy(
             database,
             metagraph,
@@ -74,6 +87,8 @@ class PygLoader:
             tls_cert,
             parallelism,
             batch_size,
+            edge_features=True,
+            graph_name=graph_name,
         )
 
         data = Data()
@@ -101,6 +116,20 @@ class PygLoader:
                 if result.numel() > 0:
                     data["edge_index"] = result
 
+        # and their attributes, e.g. edge_attr or edge_weight
+        e_col_spec = list(metagraph["edgeCollections"].values())[0]
+        for e_tup, features in edge_features_by_type.items():
+            if e_tup[0] != edge_col_name:
+                continue
+            for feature, feature_source_key in e_col_spec.items():
+                if feature_source_key not in features:
+                    raise PhenolError(
+                        f"Unable to load features {feature_source_key} for collection {edge_col_name}"  # noqa: E501
+                    )
+                result = _edge_feature_tensor(feature, features[feature_source_key])
+                if result.numel() > 0:
+                    data[feature] = result
+
         return data, col_to_adb_key_to_ind, col_to_ind_to_adb_key
 
     @staticmethod
@@ -114,6 +143,7 @@ class PygLoader:
         tls_cert: Any | None = None,
         parallelism: int | None = None,
         batch_size: int | None = None,
+        graph_name: str | None = None,
     ) -> tuple[
         "HeteroData",
         ArangoCollectionToArangoKeyToIndex,
@@ -123,15 +153,19 @@ class PygLoader:
             m = "Missing required dependencies. Install with `pip install phenolrs[torch]`"  # noqa: E501
             raise ImportError(m)
 
-        if "vertexCollections" not in metagraph:
-            raise PhenolError("vertexCollections not found in metagraph")
-        if "edgeCollections" not in metagraph:
-            raise PhenolError("edgeCollections not found in metagraph")
+        # With graph_name, the collections of the named graph are loaded and
+        # the metagraph only lists those whose attributes are needed
+        if graph_name is None:
+            if "vertexCollections" not in metagraph:
+                raise PhenolError("vertexCollections not found in metagraph")
+            if "edgeCollections" not in metagraph:
+                raise PhenolError("edgeCollections not found in metagraph")
 
-        if len(metagraph["vertexCollections"]) == 0:
-            raise PhenolError("vertexCollections must map to non-empty dictionary")
-        if len(metagraph["edgeCollections"]) == 0:
-            raise PhenolError("edgeCollections must map to non-empty dictionary")
+            if len(metagraph["vertexCollections"]) == 0:
+                raise PhenolError("vertexCollections must map to non-empty dictionary")
+            if len(metagraph["edgeCollections"]) == 0:
+                raise PhenolError("edgeCollections must map to non-empty dictionary")
+        metagraph = {"vertexCollections": {}, "edgeCollections": {}, **metagraph}
 
         (
             features_by_col,
@@ -139,6 +173,7 @@ class PygLoader:
             col_to_adb_key_to_ind,
             col_to_ind_to_adb_key,
             vertex_cols_source_to_output,
+            edge_features_by_type,
         ) = NumpyLoader.load_graph_to_numpy(
             database,
             metagraph,
@@ -149,10 +184,12 @@ class PygLoader:
             tls_cert,
             parallelism,
             batch_size,
+            edge_features=True,
+            graph_name=graph_name,
         )
         data = HeteroData()
         for col in features_by_col.keys():
-            col_mapping = vertex_cols_source_to_output[col]
+            col_mapping = vertex_cols_source_to_output.get(col, {})
             for feature in features_by_col[col].keys():
                 if feature == "@collection_name":
                     continue
@@ -170,4 +207,21 @@ class PygLoader:
             if result.numel() > 0:
                 data[(from_name, edge_col_name, to_name)].edge_index = result
 
+        for edge_col, features in edge_features_by_type.items():
+            edge_col_name, from_name, to_name = edge_col
+            e_col_spec = metagraph["edgeCollections"].get(edge_col_name, {})
+            edge_col_mapping = {
+                source_name: output_name
+                for output_name, source_name in e_col_spec.items()
+            }
+            for feature in features.keys():
+                # Edges of all collections are loaded with the same fields
+                if feature not in edge_col_mapping:
+                    continue
+
+                target_name = edge_col_mapping[feature]
+                result = _edge_feature_tensor(target_name, features[feature])
+                if result.numel() > 0:
+                    data[(from_name, edge_col_name, to_name)][target_name] = result
+
         return data, col_to_adb_key_to_ind, col_to_ind_to_adb_key
@@ -1,10 +1,12 @@
-from typing import Any, Dict
+import socket
+from typing import Any, Callable, Dict, Iterator
 
 import arango
 import networkx as nx
 import pytest
 from adbnx_adapter import ADBNX_Adapter
 from arango_datasets import Datasets
+from mock_arangodb import Handler, MockArangoDB
 
 connection_config: Dict[str, Any]
 
@@ -163,6 +165,54 @@ def karate_db_name() -> str:
     return "karate"
 
 
+@pytest.fixture(scope="module")
+def karate_database_config(
+    load_karate: None, karate_db_name: str, connection_information: Dict[str, Any]
+) -> Dict[str, Any]:
+    return {
+        "endpoints": [connection_information["url"]],
+        "database": karate_db_name,
+        "username": connection_information["username"],
+        "password": connection_information["password"],
+    }
+
+
+@pytest.fixture(scope="module")
+def karate_request(
+    karate_database_config: Dict[str, Any],
+) -> Callable[..., Dict[str, Any]]:
+    """Builds a request to load from the karate database."""
+
+    def build(**request: Any) -> Dict[str, Any]:
+        return {**request, "database_config": karate_database_config}
+
+    return build
+
+
+@pytest.fixture
+def mock_arangodb() -> Iterator[Callable[[Handler], MockArangoDB]]:
+    """Starts a local server answering requests with the given handler."""
+    servers: list[MockArangoDB] = []
+
+    def start(handler: Handler) -> MockArangoDB:
+        server = MockArangoDB(handler)
+        servers.append(server)
+        return server
+
+    yield start
+    for server in servers:
+        server.shutdown()
+
+
+@pytest.fixture
+def hung_arangodb() -> Iterator[str]:
+    """The endpoint of a server accepting connections but never answering."""
+    with socket.socket() as hung_server:
+        hung_server.bind(("127.0.0.1", 0))
+        hung_server.listen()
+        yield f"http://127.0.0.1:{hung_server.getsockname()[1]}"
+
+
 @pytest.fixture(scope="module")
 def load_multigraph(
     multigraph_db_name: str, connection_information: Dict[str, Any]
@@ -0,0 +1,10 @@
+import json
+from pathlib import Path
+from typing import Any, Iterable
+
+
+# The one-document-per-line format the file source reads
+def write_jsonl(path: Path, docs: Iterable[dict[str, Any]]) -> None:
+    with open(path, "w") as f:
+        for doc in docs:
+            f.write(json.dumps(doc) + "\n")
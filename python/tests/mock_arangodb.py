@@ -0,0 +1,97 @@
+import json
+import threading
+from dataclasses import dataclass, field
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from typing import Any, Callable
+
+
+@dataclass
+class Request:
+    method: str
+    path: str
+    authorization: str | None
+    body: bytes
+    # Requests sent on the same connection come from the same port
+    client_port: int
+
+    def json(self) -> Any:
+        return json.loads(self.body)
+
+
+@dataclass
+class Response:
+    status: int
+    # Sent as JSON, except for bytes which are sent as they are
+    body: Any = None
+    headers: dict[str, str] = field(default_factory=dict)
+
+
+def error_response(status: int, message: str, error_num: int | None = None) -> Response:
+    body = {
+        "error": True,
+        "code": status,
+        "errorNum": status if error_num is None else error_num,
+        "errorMessage": message,
+    }
+    return Response(status, body)
+
+
+Handler = Callable[[Request], Response]
+
+
+class MockArangoDB:
+    """Answers the requests of a load with `handler` on a local port and
+    records them, for tests which need no real database."""
+
+    def __init__(self, handler: Handler) -> None:
+        self.requests: list[Request] = []
+        requests = self.requests
+
+        class RequestHandler(BaseHTTPRequestHandler):
+            protocol_version = "HTTP/1.1"
+
+            def respond(self) -> None:
+                length = int(self.headers.get("Content-Length", 0))
+                request = Request(
+                    self.command,
+                    self.path,
+                    self.headers.get("Authorization"),
+                    self.rfile.read(length),
+                    self.client_address[1],
+                )
+                requests.append(request)
+                response = handler(request)
+                if isinstance(response.body, bytes):
+                    body = response.body
+                elif response.body is None:
+                    body = b""
+                else:
+                    body = json.dumps(response.body).encode()
+                self.send_response(response.status)
+                self.send_header("Content-Type", "application/json")
+                self.send_header("Content-Length", str(len(body)))
+                for name, value in response.headers.items():
+                    self.send_header(name, value)
+                self.end_headers()
+                self.wfile.write(body)
+
+            do_GET = do_POST = do_PUT = do_DELETE = respond
+
+            def log_message(self, *args: Any) -> None:
+                pass
+
+        self.server = ThreadingHTTPServer(("127.0.0.1", 0), RequestHandler)
+        self.server.daemon_threads = True
+        self.endpoint = f"http://127.0.0.1:{self.server.server_port}"
+        threading.Thread(target=self.server.serve_forever, daemon=True).start()
+
+    @property
+    def paths(self) -> list[str]:
+        return [request.path for request in self.requests]
+
+    def database_config(self, database: str = "_system") -> dict[str, Any]:
+        return {"endpoints": [self.endpoint], "database": database}
+
+    def shutdown(self) -> None:
+        self.server.shutdown()
+        self.server.server_close()
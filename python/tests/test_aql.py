@@ -0,0 +1,45 @@
+from typing import Any, Callable
+
+import pytest
+
+from phenolrs import graph_to_networkx_format
+
+
+def test_karate_aql_queries(karate_request: Callable[..., dict[str, Any]]) -> None:
+    request = karate_request(
+        vertex_query={
+            "query": "FOR p IN person FILTER p.club == @club RETURN p",
+            "bind_vars": {"club": "Mr. Hi"},
+        },
+        edge_query={
+            "query": """
+                FOR e IN knows
+                    FILTER DOCUMENT(e._from).club == @club
+                    FILTER DOCUMENT(e._to).club == @club
+                    RETURN {_id: e._id, _from: e._from, _to: e._to}
+            """,
+            "bind_vars": {"club": "Mr. Hi"},
+        },
+        load_config={
+            "load_all_vertex_attributes": True,
+            "load_all_edge_attributes": False,
+        },
+    )
+
+    node_dict, adj_dict, src_indices, dst_indices, *_ = graph_to_networkx_format(
+        request, {"is_directed": True, "is_multigraph": False}
+    )
+
+    assert len(node_dict) == 17
+    assert all(node["club"] == "Mr. Hi" for node in node_dict.values())
+    assert len(src_indices) == len(dst_indices) > 0
+    for from_id, adj in adj_dict["succ"].items():
+        for to_id in adj.keys():
+            assert from_id in node_dict
+            assert to_id in node_dict
+
+    with pytest.raises(ValueError):
+        graph_to_networkx_format(
+            {**request, "vertex_collections": [{"name": "person"}]},
+            {"is_directed": True},
+        )
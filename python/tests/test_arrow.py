@@ -0,0 +1,56 @@
+from pathlib import Path
+from typing import Any
+
+import pyarrow
+from jsonl import write_jsonl
+
+from phenolrs import graph_to_arrow_format
+
+
+def test_file_source_arrow_tables(tmp_path: Path) -> None:
+    people = [
+        {"_key": "1", "age": 30, "x": [1.0, 2.0], "name": "a"},
+        {"_key": "2", "age": 2.5, "x": [3, 4], "tags": {"a": 1}},
+        {"_key": "3", "x": None, "name": "c"},
+    ]
+    write_jsonl(tmp_path / "person.jsonl", people)
+    edges = [("1", "2", True), ("2", "3", False), ("3", "4", None)]
+    write_jsonl(
+        tmp_path / "knows.jsonl",
+        [
+            {"_from": f"person/{from_key}", "_to": f"person/{to_key}", "close": close}
+            for from_key, to_key, close in edges
+        ],
+    )
+    request: dict[str, Any] = {
+        "vertex_collections": [
+            {"name": "person", "fields": ["age", "x", "name", "tags"]}
+        ],
+        "edge_collections": [{"name": "knows", "fields": ["close"]}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(tmp_path)},
+    }
+
+    vertex_tables, edge_tables = graph_to_arrow_format(request)
+    assert vertex_tables["person"].num_rows == 3
+    person = pyarrow.record_batch(vertex_tables["person"])
+    assert person.schema.field("index").type == pyarrow.uint64()
+    assert person.schema.field("age").type == pyarrow.float64()
+    assert person.schema.field("x").type == pyarrow.list_(pyarrow.float64())
+    assert person.schema.field("tags").type == pyarrow.string()
+    assert person.to_pydict() == {
+        "_key": ["1", "2", "3"],
+        "index": [0, 1, 2],
+        "age": [30.0, 2.5, None],
+        "name": ["a", None, "c"],
+        "tags": [None, '{"a":1}', None],
+        "x": [[1.0, 2.0], [3.0, 4.0], None],
+    }
+
+    # The edge to the missing person/4 is skipped
+    knows = pyarrow.table(edge_tables[("knows", "person", "person")])
+    assert knows.to_pydict() == {
+        "from_index": [0, 1],
+        "to_index": [1, 2],
+        "close": [True, False],
+    }
@@ -0,0 +1,83 @@
+import asyncio
+from pathlib import Path
+from typing import Any, Callable
+
+import pytest
+from jsonl import write_jsonl
+
+from phenolrs import (
+    CancellationToken,
+    PhenolCancelledError,
+    graph_to_networkx_format_async,
+    graph_to_numpy_format_async,
+)
+
+
+def test_karate_networkx_async(karate_request: Callable[..., dict[str, Any]]) -> None:
+    request = karate_request(
+        vertex_collections=[{"name": "person"}],
+        edge_collections=[{"name": "knows"}],
+        load_config={
+            "load_all_vertex_attributes": True,
+            "load_all_edge_attributes": True,
+        },
+    )
+
+    async def load_concurrently() -> list[Any]:
+        return await asyncio.gather(
+            *(graph_to_networkx_format_async(request, {}) for _ in range(4))
+        )
+
+    results = asyncio.run(load_concurrently())
+    assert [len(node_dict) for node_dict, *_ in results] == [34] * 4
+
+    async def load_cancelled() -> None:
+        token = CancellationToken()
+        token.cancel()
+        await graph_to_networkx_format_async(request, {}, cancel_token=token)
+
+    with pytest.raises(PhenolCancelledError):
+        asyncio.run(load_cancelled())
+
+
+def test_file_source_async_extra_outputs(tmp_path: Path) -> None:
+    write_jsonl(
+        tmp_path / "person.jsonl", [{"_key": str(key), "x": key} for key in range(3)]
+    )
+    write_jsonl(
+        tmp_path / "knows.jsonl",
+        [
+            {"_from": f"person/{from_key}", "_to": f"person/{to_key}", "w": 1}
+            for from_key, to_key in [(0, 1), (1, 2)]
+        ],
+    )
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x"]}],
+        "edge_collections": [{"name": "knows", "fields": ["w"]}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(tmp_path)},
+    }
+
+    # The awaitable variants take the same options as the blocking ones
+    result = asyncio.run(
+        graph_to_numpy_format_async(
+            request, compressed_formats=["csr"], edge_features=True
+        )
+    )
+    assert len(result) == 6
+    assert list(result[4][("knows", "person", "person")]) == ["w"]
+    assert list(result[5][("knows", "person", "person")]) == ["csr"]
+
+    result = asyncio.run(
+        graph_to_networkx_format_async(
+            request, {"load_coo": True}, compressed_formats=["csc"]
+        )
+    )
+    assert len(result) == 8
+    assert list(result[7]) == ["csc"]
+    with pytest.raises(ValueError, match="requires load_coo"):
+        asyncio.run(
+            graph_to_networkx_format_async(
+                request, {"load_coo": False}, compressed_formats=["csc"]
+            )
+        )
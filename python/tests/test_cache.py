@@ -0,0 +1,70 @@
+from pathlib import Path
+from typing import Any, Callable
+
+import arango
+import pytest
+
+from phenolrs import graph_to_networkx_format
+from phenolrs.networkx import NetworkXLoader
+
+
+def test_karate_result_cache(
+    karate_db_name: str,
+    connection_information: dict[str, str],
+    karate_request: Callable[..., dict[str, Any]],
+    tmp_path: Path,
+) -> None:
+    request = karate_request(
+        vertex_collections=[{"name": "person", "fields": ["club"]}],
+        edge_collections=[{"name": "knows", "fields": []}],
+    )
+    cached_request = {**request, "cache_dir": str(tmp_path)}
+    graph_config = {"load_coo": False}
+
+    node_dict, adj_dict, *_ = graph_to_networkx_format(request, graph_config)
+    for _ in range(2):
+        cached_node_dict, cached_adj_dict, *_ = graph_to_networkx_format(
+            cached_request, graph_config
+        )
+        assert cached_node_dict == node_dict
+        assert cached_adj_dict == adj_dict
+        assert len(list(tmp_path.glob("*.graph"))) == 1
+
+    # Other graph options and any change to a collection need a new result
+    graph_to_networkx_format(cached_request, {"load_coo": False, "is_directed": False})
+    assert len(list(tmp_path.glob("*.graph"))) == 2
+
+    db = arango.ArangoClient(connection_information["url"]).db(
+        karate_db_name,
+        username=connection_information["username"],
+        password=connection_information["password"],
+    )
+    db.collection("person").insert({"_key": "cache_test", "club": "none"})
+    db.collection("person").delete("cache_test")
+    cached_node_dict, *_ = graph_to_networkx_format(cached_request, graph_config)
+    assert cached_node_dict == node_dict
+    # The result for the earlier revisions is replaced
+    assert len(list(tmp_path.glob("*.graph"))) == 2
+
+    loader_dir = tmp_path / "loader"
+    metagraph: dict[str, dict[str, set[str]]] = {
+        "vertexCollections": {"person": set()},
+        "edgeCollections": {"knows": set()},
+    }
+    for _ in range(2):
+        loader_node_dict, *_ = NetworkXLoader.load_into_networkx(
+            karate_db_name,
+            metagraph,
+            [connection_information["url"]],
+            username=connection_information["username"],
+            password=connection_information["password"],
+            load_coo=False,
+            cache_dir=str(loader_dir),
+        )
+        assert loader_node_dict.keys() == node_dict.keys()
+        assert len(list(loader_dir.glob("*.graph"))) == 1
+
+    with pytest.raises(ValueError, match="cache_dir"):
+        graph_to_networkx_format(
+            {**cached_request, "file_source": {"path": str(tmp_path)}}, graph_config
+        )
@@ -0,0 +1,86 @@
+import _thread
+import threading
+from concurrent.futures import ThreadPoolExecutor
+from typing import Any, Callable
+
+import pytest
+
+from phenolrs import (
+    CancellationToken,
+    PhenolCancelledError,
+    PhenolError,
+    graph_to_networkx_format,
+)
+from phenolrs.networkx import NetworkXLoader
+
+
+def test_karate_concurrent_loads_release_gil(
+    load_karate: None, karate_db_name: str, connection_information: dict[str, str]
+) -> None:
+    def load() -> int:
+        res = NetworkXLoader.load_into_networkx(
+            karate_db_name,
+            {
+                "vertexCollections": {"person": set()},
+                "edgeCollections": {"knows": set()},
+            },
+            [connection_information["url"]],
+            username=connection_information["username"],
+            password=connection_information["password"],
+        )
+        return len(res[0])
+
+    stop = threading.Event()
+    ticks = 0
+
+    def heartbeat() -> None:
+        nonlocal ticks
+        while not stop.is_set():
+            ticks += 1
+            stop.wait(0.001)
+
+    heartbeat_thread = threading.Thread(target=heartbeat)
+    heartbeat_thread.start()
+    try:
+        with ThreadPoolExecutor(max_workers=4) as executor:
+            sizes = list(executor.map(lambda _: load(), range(8)))
+    finally:
+        stop.set()
+        heartbeat_thread.join()
+
+    assert sizes == [34] * 8
+    assert ticks > 0
+
+
+def test_karate_cancelled_load(karate_request: Callable[..., dict[str, Any]]) -> None:
+    request = karate_request(
+        vertex_collections=[{"name": "person"}],
+        edge_collections=[{"name": "knows"}],
+        load_config={
+            "load_all_vertex_attributes": True,
+            "load_all_edge_attributes": True,
+        },
+    )
+
+    token = CancellationToken()
+    node_dict, *_ = graph_to_networkx_format(request, {}, cancel_token=token)
+    assert len(node_dict) == 34
+    assert not token.is_cancelled()
+
+    token.cancel()
+    assert token.is_cancelled()
+    with pytest.raises(PhenolCancelledError):
+        graph_to_networkx_format(request, {}, cancel_token=token)
+    assert issubclass(PhenolCancelledError, PhenolError)
+
+
+def test_interrupted_load_raises_keyboard_interrupt(hung_arangodb: str) -> None:
+    # The hung server never answers, so the load runs until interrupted
+    request: dict[str, Any] = {
+        "vertex_query": {"query": "FOR p IN person RETURN p"},
+        "database_config": {"endpoints": [hung_arangodb]},
+    }
+
+    threading.Timer(0.3, _thread.interrupt_main).start()
+    with pytest.raises(KeyboardInterrupt):
+        graph_to_networkx_format(request, {"load_coo": False})
@@ -0,0 +1,149 @@
+import json
+from pathlib import Path
+from typing import Any, Callable
+
+import pytest
+from jsonl import write_jsonl
+from mock_arangodb import Handler, MockArangoDB, Request, Response, error_response
+
+from phenolrs import PhenolError, graph_to_networkx_format
+
+
+def test_file_source_resumes_from_checkpoint(tmp_path: Path) -> None:
+    data_dir = tmp_path / "data"
+    data_dir.mkdir()
+    write_jsonl(
+        data_dir / "person.jsonl",
+        [{"_key": key, "x": int(key)} for key in ["1", "2", "3"]],
+    )
+    write_jsonl(
+        data_dir / "knows.jsonl",
+        [
+            {"_key": key, "_from": f"person/{from_key}", "_to": f"person/{to_key}"}
+            for key, from_key, to_key in [("1", "1", "2"), ("2", "2", "3")]
+        ],
+    )
+    checkpoint_dir = tmp_path / "checkpoint"
+
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x"]}],
+        "edge_collections": [{"name": "knows", "fields": []}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(data_dir)},
+        "checkpoint_dir": str(checkpoint_dir),
+        "progress_interval": 1000,
+    }
+    graph_config = {"load_coo": False}
+    expected = graph_to_networkx_format(
+        {**request, "checkpoint_dir": None}, graph_config
+    )
+
+    # Reported once all vertices are loaded, so they are in the checkpoint
+    def fail_after_vertices(phase: str, *args: Any) -> None:
+        if phase == "vertices":
+            raise RuntimeError("stop")
+
+    with pytest.raises(PhenolError, match="stop"):
+        graph_to_networkx_format(
+            {**request, "progress_callback": fail_after_vertices}, graph_config
+        )
+    assert (checkpoint_dir / "manifest.json").exists()
+
+    with pytest.raises(PhenolError, match="different request"):
+        graph_to_networkx_format(
+            {**request, "vertex_collections": [{"name": "person"}]}, graph_config
+        )
+
+    # The vertices come from the checkpoint now
+    (data_dir / "person.jsonl").unlink()
+    assert graph_to_networkx_format(request, graph_config) == expected
+    assert not checkpoint_dir.exists()
+
+
+def test_file_source_resumes_within_a_phase(tmp_path: Path) -> None:
+    data_dir = tmp_path / "data"
+    data_dir.mkdir()
+    write_jsonl(data_dir / "person.jsonl", [{"_key": key} for key in ["1", "2", "3"]])
+
+    def write_edges(edges: list[dict[str, str]]) -> None:
+        write_jsonl(data_dir / "knows.jsonl", edges)
+
+    edges = [
+        {"_key": "1", "_from": "person/1", "_to": "person/2"},
+        {"_key": "2", "_from": "person/2", "_to": "person/3"},
+        {"_key": "3", "_from": "person/3", "_to": "person/1"},
+    ]
+    checkpoint_dir = tmp_path / "checkpoint"
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": []}],
+        "edge_collections": [{"name": "knows", "fields": []}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "load_config": {"batch_size": 1},
+        "file_source": {"path": str(data_dir)},
+        "checkpoint_dir": str(checkpoint_dir),
+    }
+    graph_config = {"load_coo": False}
+    write_edges(edges)
+    expected = graph_to_networkx_format(
+        {**request, "checkpoint_dir": None}, graph_config
+    )
+
+    # Fails on the third batch of edges, the first two are in the checkpoint
+    write_edges(edges[:2] + [{"_key": "3", "_from": "person/3"}])
+    with pytest.raises(PhenolError, match="_to"):
+        graph_to_networkx_format(request, graph_config)
+    manifest = json.loads((checkpoint_dir / "manifest.json").read_text())
+    assert manifest["progress"]["edges"]["cursors"] == {"0": 2}
+
+    # The written batches are read back instead of the changed first edge
+    write_edges([{"_key": "1", "_from": "person/1", "_to": "person/3"}] + edges[1:])
+    assert graph_to_networkx_format(request, graph_config) == expected
+    assert not checkpoint_dir.exists()
+
+
+def test_checkpoint_of_changed_collection_is_not_resumed(
+    tmp_path: Path, mock_arangodb: Callable[[Handler], MockArangoDB]
+) -> None:
+    revisions = {"person": "1"}
+
+    def revisioned_server(request: Request) -> Response:
+        if request.path.endswith("/revision"):
+            return Response(200, {"revision": revisions["person"]})
+        if request.method == "GET":
+            # Without the deployment type, collections are read with AQL
+            return error_response(404, "not found")
+        result = [{"_key": "1", "_id": "person/1"}]
+        return Response(201, {"result": result, "hasMore": False})
+
+    server = mock_arangodb(revisioned_server)
+    checkpoint_dir = tmp_path / "checkpoint"
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person"}],
+        "edge_collections": [],
+        "database_config": server.database_config("revisions"),
+        "checkpoint_dir": str(checkpoint_dir),
+        "progress_interval": 1000,
+    }
+    graph_config = {"load_coo": False}
+
+    def fail_after_vertices(phase: str, *args: Any) -> None:
+        if phase == "vertices":
+            raise RuntimeError("stop")
+
+    with pytest.raises(PhenolError, match="stop"):
+        graph_to_networkx_format(
+            {**request, "progress_callback": fail_after_vertices}, graph_config
+        )
+    manifest = json.loads((checkpoint_dir / "manifest.json").read_text())
+    assert manifest["revisions"] == {"person": "1"}
+
+    revisions["person"] = "2"
+    with pytest.raises(PhenolError, match="Collections changed"):
+        graph_to_networkx_format(request, graph_config)
+
+    # The vertices come from the checkpoint again
+    revisions["person"] = "1"
+    server.requests.clear()
+    graph_to_networkx_format(request, graph_config)
+    assert "/_db/revisions/_api/cursor" not in server.paths
+    assert not checkpoint_dir.exists()
@@ -0,0 +1,57 @@
+from pathlib import Path
+from typing import Any
+
+import numpy
+import pytest
+from jsonl import write_jsonl
+
+from phenolrs import graph_to_networkx_format, graph_to_numpy_format
+
+
+def test_file_source_compressed_formats(tmp_path: Path) -> None:
+    write_jsonl(
+        tmp_path / "person.jsonl", [{"_key": str(key), "x": key} for key in range(4)]
+    )
+    write_jsonl(
+        tmp_path / "knows.jsonl",
+        [
+            {"_from": f"person/{from_key}", "_to": f"person/{to_key}"}
+            for from_key, to_key in [(2, 1), (0, 1), (2, 0), (1, 3), (0, 3)]
+        ],
+    )
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x"]}],
+        "edge_collections": [{"name": "knows", "fields": []}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(tmp_path)},
+    }
+
+    result = graph_to_numpy_format(request, compressed_formats=["csr", "csc"])
+    coo = result[1][("knows", "person", "person")]
+    compressed = result[4][("knows", "person", "person")]
+    # Rows are the vertices by index, the edges of a row keep their COO order
+    indptr, indices, permutation = compressed["csr"]
+    assert indptr.tolist() == [0, 2, 3, 5, 5]
+    numpy.testing.assert_array_equal(indices, coo[1][permutation])
+    numpy.testing.assert_array_equal(
+        numpy.repeat(range(4), [2, 1, 2, 0]), coo[0][permutation]
+    )
+    indptr, indices, permutation = compressed["csc"]
+    assert indptr.tolist() == [0, 1, 3, 3, 5]
+    numpy.testing.assert_array_equal(indices, coo[0][permutation])
+
+    graph_config: dict[str, Any] = {"load_adj_dict": False}
+    result = graph_to_networkx_format(request, graph_config, compressed_formats=["csr"])
+    src, dst, compressed = result[2], result[3], result[7]
+    indptr, indices, permutation = compressed["csr"]
+    assert list(compressed) == ["csr"]
+    assert len(indptr) == len(result[5]) + 1
+    numpy.testing.assert_array_equal(indices, dst[permutation])
+    numpy.testing.assert_array_equal(numpy.sort(src), src[permutation])
+
+    with pytest.raises(ValueError, match="Unknown compressed format"):
+        graph_to_numpy_format(request, compressed_formats=["coo"])
+    with pytest.raises(ValueError, match="requires load_coo"):
+        graph_to_networkx_format(
+            request, {"load_coo": False}, compressed_formats=["csr"]
+        )
@@ -0,0 +1,71 @@
+from pathlib import Path
+from typing import Any
+
+import numpy
+from jsonl import write_jsonl
+
+from phenolrs import (
+    graph_to_numpy_format,
+    graph_to_parquet,
+    load_numpy_parquet,
+    load_numpy_snapshot,
+    save_numpy_snapshot,
+)
+
+
+def test_file_source_edge_features(tmp_path: Path) -> None:
+    write_jsonl(
+        tmp_path / "person.jsonl", [{"_key": str(key), "x": key} for key in range(3)]
+    )
+    edges: list[dict[str, Any]] = [
+        {"_from": "person/0", "_to": "person/1", "weight": 0.5, "v": [1, 2]},
+        {"_from": "person/1", "_to": "person/2", "v": [3, 4]},
+        {"_from": "person/1", "_to": "person/9", "weight": 1.0},
+        {"_from": "person/2", "_to": "person/0", "weight": 2, "v": "a"},
+    ]
+    write_jsonl(tmp_path / "knows.jsonl", edges)
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x"]}],
+        "edge_collections": [{"name": "knows", "fields": ["weight", "v"]}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(tmp_path)},
+    }
+
+    result = graph_to_numpy_format(request, edge_features=True)
+    assert len(result) == 5
+    coo = result[1][("knows", "person", "person")]
+    features = result[4][("knows", "person", "person")]
+    # Rows follow the COO columns, the edge to a missing vertex is skipped
+    assert coo.tolist() == [[0, 1, 2], [1, 2, 0]]
+    numpy.testing.assert_array_equal(features["weight"], [[0.5], [numpy.nan], [2]])
+    numpy.testing.assert_array_equal(
+        features["v"], [[1, 2], [3, 4], [numpy.nan, numpy.nan]]
+    )
+
+    result = graph_to_numpy_format(
+        request, compressed_formats=["csr"], edge_features=True
+    )
+    assert set(result[4]) == {("knows", "person", "person")}
+    assert list(result[5][("knows", "person", "person")]) == ["csr"]
+    assert len(graph_to_numpy_format(request)) == 4
+
+    # Snapshots and Parquet exports keep the edge features
+    save_numpy_snapshot(tmp_path / "numpy.snapshot", result)
+    graph_to_parquet(request, tmp_path / "parquet")
+    for restored in [
+        load_numpy_snapshot(tmp_path / "numpy.snapshot", edge_features=True),
+        load_numpy_parquet(tmp_path / "parquet", edge_features=True),
+    ]:
+        assert len(restored) == 5
+        features = restored[4][("knows", "person", "person")]
+        numpy.testing.assert_array_equal(features["weight"], [[0.5], [numpy.nan], [2]])
+        numpy.testing.assert_array_equal(
+            features["v"], [[1, 2], [3, 4], [numpy.nan, numpy.nan]]
+        )
+    assert len(load_numpy_snapshot(tmp_path / "numpy.snapshot")) == 4
+
+    # Compressed formats in place of the edge features are not taken for them
+    compressed = graph_to_numpy_format(request, compressed_formats=["csr"])
+    save_numpy_snapshot(tmp_path / "compressed.snapshot", compressed)
+    restored = load_numpy_snapshot(tmp_path / "compressed.snapshot", edge_features=True)
+    assert restored[4] == {}
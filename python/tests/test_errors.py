@@ -0,0 +1,106 @@
+from pathlib import Path
+from typing import Any, Callable
+
+import pytest
+from jsonl import write_jsonl
+from mock_arangodb import Handler, MockArangoDB, Request, Response, error_response
+
+from phenolrs import (
+    PhenolAuthenticationError,
+    PhenolCollectionNotFoundError,
+    PhenolDuplicateEdgeError,
+    PhenolError,
+    PhenolSchemaError,
+    graph_to_networkx_format,
+    inspect_graph,
+)
+
+
+def test_karate_structured_errors(
+    karate_request: Callable[..., dict[str, Any]], tmp_path: Path
+) -> None:
+    request = karate_request(
+        vertex_collections=[{"name": "person", "fields": []}],
+        edge_collections=[{"name": "missing", "fields": []}],
+    )
+    graph_config = {"is_directed": False, "is_multigraph": False}
+
+    with pytest.raises(PhenolCollectionNotFoundError) as e:
+        graph_to_networkx_format(request, graph_config)
+    assert e.value.collection == "missing"
+
+    with pytest.raises(PhenolAuthenticationError):
+        graph_to_networkx_format(
+            {
+                **request,
+                "database_config": {
+                    **request["database_config"],
+                    "password": "wrong password",
+                },
+            },
+            graph_config,
+        )
+
+    with pytest.raises(PhenolSchemaError) as e:
+        graph_to_networkx_format(
+            {**request, "edge_collections": [{"name": "knows", "fields": ["_key"]}]},
+            graph_config,
+        )
+    assert e.value.collection == "knows"
+    assert e.value.attribute == "_key"
+    assert "Edge data must be a numeric value" in str(e.value)
+
+    write_jsonl(tmp_path / "person.jsonl", [{"_key": key} for key in ["1", "2"]])
+    write_jsonl(
+        tmp_path / "knows.jsonl",
+        [{"_key": key, "_from": "person/1", "_to": "person/2"} for key in ["1", "2"]],
+    )
+    file_request = {
+        **request,
+        "edge_collections": [{"name": "knows", "fields": []}],
+        "file_source": {"path": str(tmp_path)},
+    }
+
+    with pytest.raises(PhenolDuplicateEdgeError) as e:
+        graph_to_networkx_format(file_request, graph_config)
+    assert e.value.from_id == "person/1"
+    assert e.value.to_id == "person/2"
+
+    with pytest.raises(PhenolCollectionNotFoundError) as e:
+        graph_to_networkx_format(
+            {**file_request, "vertex_collections": [{"name": "pet", "fields": []}]},
+            graph_config,
+        )
+    assert e.value.collection == "pet"
+    assert issubclass(PhenolCollectionNotFoundError, PhenolError)
+
+
+def test_missing_collection_is_named_exactly(
+    mock_arangodb: Callable[[Handler], MockArangoDB],
+) -> None:
+    def missing_edges(request: Request) -> Response:
+        if request.method == "GET":
+            # Without the deployment type, collections are read with AQL
+            return error_response(404, "not found")
+        if "user_edges" in request.json()["bindVars"].values():
+            # Also contains the name of the existing collection `user`
+            message = "AQL: collection or view not found: user_edges"
+            return error_response(404, message, error_num=1203)
+        result = [{"_key": "1", "_id": "user/1"}]
+        return Response(201, {"result": result, "hasMore": False})
+
+    server = mock_arangodb(missing_edges)
+    request = {
+        "vertex_collections": [{"name": "user"}],
+        "edge_collections": [{"name": "user_edges"}],
+        "database_config": server.database_config(),
+        "load_config": {"load_all_edge_attributes": True},
+    }
+
+    with pytest.raises(PhenolCollectionNotFoundError) as e:
+        graph_to_networkx_format(request, {})
+    assert e.value.collection == "user_edges"
+
+    with pytest.raises(PhenolCollectionNotFoundError) as e:
+        inspect_graph(request)
+    assert e.value.collection == "user_edges"
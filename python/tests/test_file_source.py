@@ -0,0 +1,88 @@
+import gzip
+import json
+from pathlib import Path
+from typing import Any, Callable
+
+import arango
+import pytest
+from jsonl import write_jsonl
+
+from phenolrs import (
+    PhenolError,
+    PhenolSchemaError,
+    graph_to_networkx_format,
+    graph_to_numpy_format,
+)
+
+
+def test_karate_file_source(
+    karate_db_name: str,
+    connection_information: dict[str, str],
+    karate_request: Callable[..., dict[str, Any]],
+    tmp_path: Path,
+) -> None:
+    client = arango.ArangoClient(connection_information["url"])
+    db = client.db(
+        karate_db_name,
+        username=connection_information["username"],
+        password=connection_information["password"],
+    )
+
+    # arangodump does not write _id, so leave it out of the files
+    people = list(db.collection("person").all())
+    for doc in people:
+        doc.pop("_id")
+    write_jsonl(tmp_path / "person.jsonl", people)
+    # arangodump naming and envelope format, gzipped
+    dump_file = tmp_path / "knows_0123456789abcdef0123456789abcdef.data.json.gz"
+    with gzip.open(dump_file, "wt") as f:
+        for doc in db.collection("knows").all():
+            doc.pop("_id")
+            envelope = {"type": 2300, "key": doc["_key"], "data": doc}
+            f.write(json.dumps(envelope) + "\n")
+
+    request = karate_request(
+        vertex_collections=[{"name": "person", "fields": []}],
+        edge_collections=[{"name": "knows", "fields": []}],
+        load_config={
+            "load_all_vertex_attributes": True,
+            "load_all_edge_attributes": True,
+        },
+    )
+    graph_config = {"is_directed": True, "is_multigraph": True}
+
+    db_node_dict, db_adj_dict, *_ = graph_to_networkx_format(request, graph_config)
+    file_node_dict, file_adj_dict, *_ = graph_to_networkx_format(
+        {**request, "file_source": {"path": str(tmp_path)}}, graph_config
+    )
+    assert file_node_dict == db_node_dict
+    assert file_adj_dict == db_adj_dict
+
+    with pytest.raises(PhenolError):
+        graph_to_networkx_format(
+            {**request, "file_source": {"path": str(tmp_path / "missing")}},
+            graph_config,
+        )
+
+
+def test_file_source_schema_errors(tmp_path: Path) -> None:
+    write_jsonl(
+        tmp_path / "person.jsonl", [{"_key": key, "x": int(key)} for key in ["1", "2"]]
+    )
+    write_jsonl(
+        tmp_path / "knows.jsonl", [{"_key": "1", "_from": "person/1", "_to": "person2"}]
+    )
+
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x"]}],
+        "edge_collections": [{"name": "knows", "fields": ["_id"]}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(tmp_path)},
+    }
+
+    # A malformed edge fails the load instead of crashing its thread
+    with pytest.raises(PhenolSchemaError) as e:
+        graph_to_numpy_format(request)
+    assert e.value.document_id == "knows/1"
+    assert e.value.attribute == "_to"
+    assert "Invalid format for _to" in str(e.value)
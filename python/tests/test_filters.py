@@ -0,0 +1,43 @@
+from typing import Any, Callable
+
+import pytest
+
+from phenolrs import graph_to_networkx_format
+
+
+def test_karate_collection_filters(
+    karate_request: Callable[..., dict[str, Any]],
+) -> None:
+    request = karate_request(
+        vertex_collections=[
+            {"name": "person", "filter": 'doc.club == "Mr. Hi"'},
+        ],
+        edge_collections=[{"name": "knows"}],
+        load_config={
+            "load_all_vertex_attributes": True,
+            "load_all_edge_attributes": True,
+        },
+    )
+
+    node_dict, adj_dict, src_indices, dst_indices, _, id_to_index, _ = (
+        graph_to_networkx_format(
+            request, {"is_directed": True, "is_multigraph": False}
+        )
+    )
+
+    assert len(node_dict) == 17
+    assert all(node["club"] == "Mr. Hi" for node in node_dict.values())
+    # edges to filtered out vertices are dropped instead of adding the endpoints
+    assert set(id_to_index.keys()) <= set(node_dict.keys())
+    for from_id, adj in adj_dict["succ"].items():
+        assert from_id in node_dict
+        assert all(to_id in node_dict for to_id in adj.keys())
+
+    with pytest.raises(ValueError):
+        graph_to_networkx_format(
+            {
+                **request,
+                "vertex_collections": [{"name": "person", "filter": "doc.club = 1"}],
+            },
+            {"is_directed": True},
+        )
@@ -0,0 +1,45 @@
+from typing import Any, Callable
+
+import pytest
+
+from phenolrs import PhenolCollectionNotFoundError, inspect_graph
+
+
+def test_karate_inspect_graph(karate_request: Callable[..., dict[str, Any]]) -> None:
+    request = karate_request(
+        vertex_collections=[{"name": "person", "fields": ["club", "age"]}],
+        edge_collections=[{"name": "knows", "fields": ["weight"]}],
+    )
+
+    inspection = inspect_graph(request, sample_size=10)
+    person = inspection["vertex_collections"]["person"]
+    assert person["count"] == 34
+    assert person["sampled"] == 10
+    assert person["attributes"]["club"] == {
+        "types": {"string": 10},
+        "array_lengths": [],
+        "missing": 0,
+    }
+    # Requested but not in the data
+    assert person["attributes"]["age"] == {
+        "types": {},
+        "array_lengths": [],
+        "missing": 10,
+    }
+    knows = inspection["edge_collections"]["knows"]
+    assert knows["count"] == 78
+    assert knows["attributes"]["weight"]["types"] == {"number": 10}
+    assert inspection["edge_triples"] == [("knows", "person", "person")]
+
+    # Without fields every attribute is reported
+    all_attributes = inspect_graph(
+        {**request, "vertex_collections": [{"name": "person"}]}
+    )
+    assert set(all_attributes["vertex_collections"]["person"]["attributes"]) == {"club"}
+
+    with pytest.raises(PhenolCollectionNotFoundError) as e:
+        inspect_graph({**request, "vertex_collections": [{"name": "pet"}]})
+    assert e.value.collection == "pet"
+
+    with pytest.raises(ValueError, match="collections of a database"):
+        inspect_graph({**request, "file_source": {"path": "."}})
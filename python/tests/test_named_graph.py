@@ -0,0 +1,79 @@
+from typing import Any, Callable
+
+import pytest
+from mock_arangodb import Handler, MockArangoDB, Request, Response, error_response
+
+from phenolrs import PhenolError, graph_to_networkx_format, inspect_graph
+from phenolrs.networkx import NetworkXLoader
+
+
+def test_karate_named_graph(
+    karate_database_config: dict[str, Any],
+    karate_db_name: str,
+    connection_information: dict[str, Any],
+) -> None:
+    explicit_request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["club"]}],
+        "edge_collections": [{"name": "knows", "fields": []}],
+        "database_config": karate_database_config,
+    }
+    # The graph was created with the name of the database, person keeps its
+    # fields, knows is taken from the edge definitions
+    named_request: dict[str, Any] = {
+        "graph_name": karate_db_name,
+        "vertex_collections": [{"name": "person", "fields": ["club"]}],
+        "database_config": karate_database_config,
+    }
+    graph_config = {"load_coo": False}
+
+    explicit_node_dict, explicit_adj_dict, *_ = graph_to_networkx_format(
+        explicit_request, graph_config
+    )
+    named_node_dict, named_adj_dict, *_ = graph_to_networkx_format(
+        named_request, graph_config
+    )
+    assert named_node_dict == explicit_node_dict
+    assert named_adj_dict == explicit_adj_dict
+
+    inspection = inspect_graph(named_request)
+    assert set(inspection["vertex_collections"]) == {"person"}
+    assert set(inspection["edge_collections"]) == {"knows"}
+
+    with pytest.raises(PhenolError, match="not part of graph"):
+        graph_to_networkx_format(
+            {**named_request, "vertex_collections": [{"name": "pet"}]}, graph_config
+        )
+
+    with pytest.raises(PhenolError, match="missing_graph"):
+        graph_to_networkx_format(
+            {**named_request, "graph_name": "missing_graph"}, graph_config
+        )
+
+    loader_node_dict, loader_adj_dict, *_ = NetworkXLoader.load_into_networkx(
+        karate_db_name,
+        {},
+        [connection_information["url"]],
+        username=connection_information["username"],
+        password=connection_information["password"],
+        load_coo=False,
+        graph_name=karate_db_name,
+    )
+    assert loader_node_dict.keys() == explicit_node_dict.keys()
+    assert loader_adj_dict == explicit_adj_dict
+
+
+def test_graph_name_is_url_encoded(
+    mock_arangodb: Callable[[Handler], MockArangoDB],
+) -> None:
+    def missing_graph(request: Request) -> Response:
+        return error_response(404, "graph 'a b/c?d' not found", 1924)
+
+    server = mock_arangodb(missing_graph)
+    request = {
+        "graph_name": "a b/c?d",
+        "database_config": server.database_config("graphs"),
+    }
+
+    with pytest.raises(PhenolError, match="Could not read graph 'a b/c\\?d'"):
+        graph_to_networkx_format(request, {})
+    assert server.paths == ["/_db/graphs/_api/gharial/a%20b%2Fc%3Fd"]
@@ -0,0 +1,68 @@
+from pathlib import Path
+from typing import Any
+
+import numpy
+import pyarrow
+import pyarrow.dataset
+import pytest
+from jsonl import write_jsonl
+
+from phenolrs import (
+    PhenolError,
+    graph_to_numpy_format,
+    graph_to_parquet,
+    load_numpy_parquet,
+)
+
+
+def test_file_source_parquet_export(tmp_path: Path) -> None:
+    data_dir = tmp_path / "data"
+    data_dir.mkdir()
+    people = []
+    for key in range(1, 6):
+        person: dict[str, Any] = {"_key": str(key), "x": [key, 0.5]}
+        if key % 2:
+            person["y"] = key
+        people.append(person)
+    write_jsonl(data_dir / "person.jsonl", people)
+    write_jsonl(
+        data_dir / "knows.jsonl",
+        [
+            {"_from": f"person/{from_key}", "_to": f"person/{to_key}"}
+            for from_key, to_key in [(1, 2), (2, 3), (3, 9), (5, 1)]
+        ],
+    )
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x", "y"]}],
+        "edge_collections": [{"name": "knows", "fields": []}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(data_dir)},
+    }
+
+    graph_to_parquet(request, tmp_path / "export")
+    assert (tmp_path / "export" / "_SUCCESS").exists()
+    with pytest.raises(PhenolError, match="is not empty"):
+        graph_to_parquet(request, tmp_path / "export")
+
+    features, coo, keys_to_inds, inds_to_keys = graph_to_numpy_format(request)
+    exported = load_numpy_parquet(tmp_path / "export")
+    for name in ["x", "y"]:
+        numpy.testing.assert_array_equal(
+            exported[0]["person"][name], features["person"][name]
+        )
+    numpy.testing.assert_array_equal(
+        exported[1][("knows", "person", "person")], coo[("knows", "person", "person")]
+    )
+    assert exported[2] == keys_to_inds
+    assert exported[3] == inds_to_keys
+
+    # The partitions are read as columns, vertices lacking a feature have null
+    vertices = pyarrow.dataset.dataset(
+        tmp_path / "export" / "vertices", partitioning="hive"
+    ).to_table()
+    assert vertices.column("collection").to_pylist() == ["person"] * 5
+    assert vertices.column("y").to_pylist() == [[1.0], None, [3.0], None, [5.0]]
+
+    (tmp_path / "export" / "_SUCCESS").unlink()
+    with pytest.raises(PhenolError, match="not a complete Parquet export"):
+        load_numpy_parquet(tmp_path / "export")
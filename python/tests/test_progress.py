@@ -0,0 +1,43 @@
+from typing import Any, Callable
+
+import pytest
+
+from phenolrs import PhenolError, graph_to_networkx_format
+
+
+def test_karate_progress_callback(
+    karate_request: Callable[..., dict[str, Any]],
+) -> None:
+    calls: list[tuple[str, str, int, float]] = []
+
+    def on_progress(
+        phase: str, collection: str, processed: int, elapsed: float
+    ) -> None:
+        calls.append((phase, collection, processed, elapsed))
+
+    request = karate_request(
+        vertex_collections=[{"name": "person"}],
+        edge_collections=[{"name": "knows"}],
+        load_config={
+            "load_all_vertex_attributes": True,
+            "load_all_edge_attributes": True,
+        },
+        progress_callback=on_progress,
+        progress_interval=10,
+    )
+    graph_to_networkx_format(request, {})
+
+    vertex_calls = [c for c in calls if c[0] == "vertices"]
+    edge_calls = [c for c in calls if c[0] == "edges"]
+    assert {c[1] for c in vertex_calls} == {"person"}
+    assert {c[1] for c in edge_calls} == {"knows"}
+    assert vertex_calls[-1][2] == 34
+    assert edge_calls[-1][2] == 78
+    assert all(c[3] >= 0 for c in calls)
+    assert calls.index(vertex_calls[-1]) < calls.index(edge_calls[0])
+
+    def failing_callback(*args: Any) -> None:
+        raise RuntimeError("stop")
+
+    with pytest.raises(PhenolError, match="stop"):
+        graph_to_networkx_format({**request, "progress_callback": failing_callback}, {})
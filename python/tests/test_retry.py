@@ -0,0 +1,129 @@
+from typing import Any, Callable
+
+import pytest
+from mock_arangodb import Handler, MockArangoDB, Request, Response, error_response
+
+from phenolrs import PhenolError, graph_to_networkx_format
+
+
+def test_aql_query_retries_unavailable_server(
+    mock_arangodb: Callable[[Handler], MockArangoDB],
+) -> None:
+    def flaky_coordinator(request: Request) -> Response:
+        if len(server.requests) <= 2:
+            return error_response(503, "service unavailable")
+        result = [{"_id": "person/1"}, {"_id": "person/2"}]
+        return Response(201, {"result": result, "hasMore": False})
+
+    server = mock_arangodb(flaky_coordinator)
+    request: dict[str, Any] = {
+        "vertex_query": {"query": "FOR p IN person RETURN p"},
+        "database_config": server.database_config("flaky"),
+        "load_config": {
+            "load_all_vertex_attributes": True,
+            "retry": {"max_attempts": 3, "initial_backoff": 0.01},
+        },
+    }
+    graph_config = {"load_adj_dict": True, "load_coo": False}
+
+    node_dict, *_ = graph_to_networkx_format(request, graph_config)
+    assert set(node_dict.keys()) == {"person/1", "person/2"}
+    assert server.paths == ["/_db/flaky/_api/cursor"] * 3
+
+    # Out of attempts, the last response is reported
+    server.requests.clear()
+    request["load_config"]["retry"]["max_attempts"] = 2
+    with pytest.raises(PhenolError, match="503"):
+        graph_to_networkx_format(request, graph_config)
+    assert len(server.requests) == 2
+
+    # 503 is no longer retried if not listed
+    server.requests.clear()
+    request["load_config"]["retry"] = {"retryable_status_codes": [502]}
+    with pytest.raises(PhenolError, match="503"):
+        graph_to_networkx_format(request, graph_config)
+    assert len(server.requests) == 1
+
+    with pytest.raises(ValueError, match="max_attempts"):
+        graph_to_networkx_format(
+            {**request, "load_config": {"retry": {"max_attempts": 0}}}, graph_config
+        )
+
+
+def dump_server(failures: dict[str, int]) -> Handler:
+    """Serves the collection person in two dump batches, failing each batch
+    id in `failures` with a 503 as often as given."""
+    batches = {
+        "0": b'{"_key": "1", "_id": "person/1"}\n',
+        "1": b'{"_key": "2", "_id": "person/2"}\n',
+    }
+
+    def handler(request: Request) -> Response:
+        if request.method == "GET":
+            if request.path == "/_admin/support-info":
+                return Response(200, {"deployment": {"type": "single"}})
+            version = {"server": "arango", "license": "community", "version": "3.12.0"}
+            return Response(200, version)
+        if request.method == "DELETE":
+            return Response(200, {})
+        if "/_api/dump/start" in request.path:
+            return Response(201, headers={"X-Arango-Dump-Id": "dump-1"})
+        batch_id = request.path.split("batchId=")[1].split("&")[0]
+        if failures.get(batch_id, 0) > 0:
+            failures[batch_id] -= 1
+            return error_response(503, "service unavailable")
+        if batch_id in batches:
+            return Response(200, batches[batch_id])
+        return Response(204)
+
+    return handler
+
+
+def test_collection_load_retries_failed_batch(
+    mock_arangodb: Callable[[Handler], MockArangoDB],
+) -> None:
+    server = mock_arangodb(dump_server({"1": 1}))
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person"}],
+        "edge_collections": [],
+        "database_config": server.database_config("flaky"),
+        "load_config": {
+            "parallelism": 1,
+            "load_all_vertex_attributes": True,
+            "retry": {"max_attempts": 3, "initial_backoff": 0.01},
+        },
+    }
+    graph_config = {"load_adj_dict": True, "load_coo": False}
+
+    node_dict, *_ = graph_to_networkx_format(request, graph_config)
+    assert set(node_dict.keys()) == {"person/1", "person/2"}
+    # The load already handed in the first batch, only the second is repeated
+    next_paths = [path for path in server.paths if "/dump/next/" in path]
+    assert next_paths == [
+        "/_db/flaky/_api/dump/next/dump-1?dbserver=person&batchId=0",
+        "/_db/flaky/_api/dump/next/dump-1?dbserver=person&batchId=1&lastBatch=0",
+        "/_db/flaky/_api/dump/next/dump-1?dbserver=person&batchId=1&lastBatch=0",
+        "/_db/flaky/_api/dump/next/dump-1?dbserver=person&batchId=2&lastBatch=1",
+    ]
+    assert server.paths[-1] == "/_db/flaky/_api/dump/dump-1"
+
+
+def test_collection_load_fails_on_exhausted_batch(
+    mock_arangodb: Callable[[Handler], MockArangoDB],
+) -> None:
+    server = mock_arangodb(dump_server({"1": 2}))
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person"}],
+        "edge_collections": [],
+        "database_config": server.database_config("flaky"),
+        "load_config": {
+            "parallelism": 1,
+            "load_all_vertex_attributes": True,
+            "retry": {"max_attempts": 2, "initial_backoff": 0.01},
+        },
+    }
+
+    with pytest.raises(PhenolError, match="503"):
+        graph_to_networkx_format(request, {"load_adj_dict": True, "load_coo": False})
+    # The dump context is released all the same
+    assert server.paths[-1] == "/_db/flaky/_api/dump/dump-1"
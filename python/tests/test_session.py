@@ -0,0 +1,55 @@
+from typing import Any, Callable
+
+import pytest
+from mock_arangodb import Handler, MockArangoDB, Request, Response, error_response
+
+from phenolrs import Session
+
+
+def test_karate_session(
+    karate_database_config: dict[str, Any], karate_db_name: str
+) -> None:
+    session = Session(karate_database_config)
+    request = {
+        "vertex_collections": [{"name": "person"}],
+        "edge_collections": [{"name": "knows"}],
+    }
+
+    for _ in range(3):
+        node_dict, *_ = session.load_networkx(request, {})
+        assert len(node_dict) == 34
+
+    with pytest.raises(ValueError, match="database_config"):
+        session.load_networkx(
+            {**request, "database_config": {"database": karate_db_name}}, {}
+        )
+
+
+def test_session_collection_loads_use_its_token(
+    mock_arangodb: Callable[[Handler], MockArangoDB],
+) -> None:
+    def token_server(request: Request) -> Response:
+        if request.method == "GET":
+            # Without the deployment type, collections are read with AQL
+            return error_response(404, "not found")
+        if request.path == "/_open/auth":
+            return Response(200, {"jwt": "session-token"})
+        result = [{"_key": "1", "_id": "person/1"}]
+        return Response(201, {"result": result, "hasMore": False})
+
+    server = mock_arangodb(token_server)
+    session = Session(
+        {**server.database_config("sessions"), "username": "root", "password": "x"}
+    )
+    request = {"vertex_collections": [{"name": "person"}], "edge_collections": []}
+
+    for _ in range(2):
+        node_dict, *_ = session.load_networkx(request, {})
+        assert set(node_dict.keys()) == {"person/1"}
+    assert server.paths.count("/_open/auth") == 1
+    assert "/_db/sessions/_api/cursor" in server.paths
+    assert {r.authorization for r in server.requests if r.path != "/_open/auth"} == {
+        "Bearer session-token"
+    }
+    # Collection loads go through the session's client and its connection
+    assert len({r.client_port for r in server.requests}) == 1
@@ -0,0 +1,81 @@
+from pathlib import Path
+from typing import Any
+
+import numpy
+import pytest
+from jsonl import write_jsonl
+
+from phenolrs import (
+    PhenolError,
+    graph_to_networkx_format,
+    graph_to_numpy_format,
+    load_networkx_snapshot,
+    load_numpy_snapshot,
+    save_networkx_snapshot,
+    save_numpy_snapshot,
+)
+
+
+def test_file_source_snapshots(tmp_path: Path) -> None:
+    data_dir = tmp_path / "data"
+    data_dir.mkdir()
+    write_jsonl(
+        data_dir / "person.jsonl",
+        [
+            {"_key": key, "x": [int(key), 0.5], "name": f"p{key}"}
+            for key in ["1", "2", "3"]
+        ],
+    )
+    edges = [("1", "1", "2"), ("2", "2", "3"), ("3", "1", "2")]
+    write_jsonl(
+        data_dir / "knows.jsonl",
+        [
+            {
+                "_key": key,
+                "_from": f"person/{from_key}",
+                "_to": f"person/{to_key}",
+                "weight": int(key),
+            }
+            for key, from_key, to_key in edges
+        ],
+    )
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person", "fields": ["x", "name"]}],
+        "edge_collections": [{"name": "knows", "fields": ["weight"]}],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "file_source": {"path": str(data_dir)},
+    }
+
+    features, coo, keys_to_inds, inds_to_keys = graph_to_numpy_format(request)
+    save_numpy_snapshot(
+        tmp_path / "numpy.snapshot", (features, coo, keys_to_inds, inds_to_keys)
+    )
+    (data_dir / "person.jsonl").rename(data_dir / "moved")
+    snapshot = load_numpy_snapshot(tmp_path / "numpy.snapshot")
+    (data_dir / "moved").rename(data_dir / "person.jsonl")
+    numpy.testing.assert_array_equal(
+        snapshot[0]["person"]["x"], features["person"]["x"]
+    )
+    numpy.testing.assert_array_equal(
+        snapshot[1][("knows", "person", "person")], coo[("knows", "person", "person")]
+    )
+    assert snapshot[2] == keys_to_inds
+    assert snapshot[3] == inds_to_keys
+
+    # A multigraph, its adjacency is keyed by edge index
+    graph_config: dict[str, Any] = {"is_directed": True, "is_multigraph": True}
+    result = graph_to_networkx_format(request, graph_config)
+    save_networkx_snapshot(tmp_path / "networkx.snapshot", result, graph_config)
+    snapshot = load_networkx_snapshot(tmp_path / "networkx.snapshot")
+    node_dict, adj_dict, src, dst, edge_indices, id_to_index, edge_values = result
+    assert snapshot[0] == node_dict
+    assert snapshot[1] == adj_dict
+    for restored, loaded in zip(snapshot[2:5], [src, dst, edge_indices]):
+        numpy.testing.assert_array_equal(restored, loaded)
+    assert snapshot[5] == id_to_index
+    assert snapshot[6] == edge_values
+
+    with pytest.raises(PhenolError, match="networkx graph"):
+        load_numpy_snapshot(tmp_path / "networkx.snapshot")
+    with pytest.raises(PhenolError, match="not a phenolrs snapshot"):
+        load_numpy_snapshot(data_dir / "knows.jsonl")
@@ -0,0 +1,76 @@
+import time
+from pathlib import Path
+from typing import Any
+
+import pytest
+from jsonl import write_jsonl
+
+from phenolrs import PhenolTimeoutError, graph_to_networkx_format
+
+
+def test_aql_query_times_out_on_hung_server(hung_arangodb: str) -> None:
+    request: dict[str, Any] = {
+        "vertex_query": {"query": "FOR p IN person RETURN p"},
+        "database_config": {"endpoints": [hung_arangodb]},
+        "load_config": {"retry": {"max_attempts": 1}},
+    }
+    graph_config = {"load_adj_dict": True, "load_coo": False}
+
+    with pytest.raises(PhenolTimeoutError, match="within"):
+        graph_to_networkx_format(
+            {
+                **request,
+                "load_config": {**request["load_config"], "load_deadline": 0.5},
+            },
+            graph_config,
+        )
+
+    request["database_config"]["request_timeout"] = 0.5
+    with pytest.raises(PhenolTimeoutError):
+        graph_to_networkx_format(request, graph_config)
+
+    with pytest.raises(ValueError, match="connect_timeout"):
+        graph_to_networkx_format(
+            {**request, "database_config": {"connect_timeout": 0}}, graph_config
+        )
+
+
+def test_collection_load_times_out_on_hung_server(hung_arangodb: str) -> None:
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person"}],
+        "edge_collections": [],
+        "database_config": {"endpoints": [hung_arangodb], "request_timeout": 0.5},
+        "load_config": {"retry": {"max_attempts": 1}},
+    }
+
+    with pytest.raises(PhenolTimeoutError):
+        graph_to_networkx_format(request, {})
+
+
+def test_file_load_times_out_within_a_batch(tmp_path: Path) -> None:
+    write_jsonl(tmp_path / "person.jsonl", [{"_key": str(key)} for key in range(10)])
+    reports: list[int] = []
+
+    # Every batch takes a while, the whole file much longer than the deadline
+    def slow_progress(phase: str, collection: str, count: int, *args: Any) -> None:
+        reports.append(count)
+        time.sleep(0.2)
+
+    request: dict[str, Any] = {
+        "vertex_collections": [{"name": "person"}],
+        "edge_collections": [],
+        "database_config": {"endpoints": ["http://localhost:8529"]},
+        "load_config": {"batch_size": 1, "load_deadline": 0.05},
+        "file_source": {"path": str(tmp_path)},
+        "progress_callback": slow_progress,
+        "progress_interval": 1,
+    }
+
+    start = time.monotonic()
+    with pytest.raises(PhenolTimeoutError, match="within"):
+        graph_to_networkx_format(request, {})
+    assert time.monotonic() - start < 1
+
+    # The file is no longer read once the deadline passed
+    time.sleep(0.5)
+    assert len(reports) < 10
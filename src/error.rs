@@ -0,0 +1,265 @@
+use arangors_graph_exporter::errors::GraphLoaderError;
+use reqwest::StatusCode;
+use std::any::Any;
+use std::fmt;
+use std::time::Duration;
+use tokio::task::JoinError;
+
+// ArangoDB error number for a collection or view which does not exist
+const ERROR_ARANGO_DATA_SOURCE_NOT_FOUND: i32 = 1203;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    // The database could not be reached or a request failed in transit
+    Connection,
+    // The database rejected the credentials or the user lacks permissions
+    Authentication,
+    CollectionNotFound {
+        collection: Option<String>,
+    },
+    // A document does not have the shape the graph needs, e.g. a feature
+    // which is not numeric
+    Schema {
+        collection: Option<String>,
+        document_id: Option<String>,
+        attribute: Option<String>,
+    },
+    // An edge which a (non-multi) graph already contains
+    DuplicateEdge {
+        from_id: String,
+        to_id: String,
+    },
+    Cancelled,
+    // The load deadline passed or a request timed out
+    Timeout,
+    // A bug, e.g. a worker of the load panicked
+    Internal,
+    Other,
+}
+
+// The error of a failed load. The message is meant for humans, everything
+// a caller may want to act on is in the kind.
+#[derive(Clone, Debug)]
+pub struct LoadError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl LoadError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        LoadError {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn cancelled() -> Self {
+        LoadError::new(ErrorKind::Cancelled, "Load was cancelled")
+    }
+
+    pub fn timed_out(deadline: Duration) -> Self {
+        LoadError::new(
+            ErrorKind::Timeout,
+            format!("Load did not finish within {:?}", deadline),
+        )
+    }
+
+    pub fn schema(
+        collection: Option<&str>,
+        document_id: Option<&str>,
+        attribute: Option<&str>,
+        message: impl Into<String>,
+    ) -> Self {
+        let kind = ErrorKind::Schema {
+            collection: collection.map(str::to_string),
+            document_id: document_id.map(str::to_string),
+            attribute: attribute.map(str::to_string),
+        };
+        LoadError::new(kind, message)
+    }
+
+    // Prefixes the message, keeping the kind
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<String> for LoadError {
+    fn from(message: String) -> Self {
+        LoadError::new(ErrorKind::Other, message)
+    }
+}
+
+impl From<&str> for LoadError {
+    fn from(message: &str) -> Self {
+        LoadError::new(ErrorKind::Other, message)
+    }
+}
+
+// The graphs report errors with anyhow, structured ones wrap a `LoadError`
+impl From<anyhow::Error> for LoadError {
+    fn from(error: anyhow::Error) -> Self {
+        error
+            .downcast::<LoadError>()
+            .unwrap_or_else(|e| LoadError::from(e.to_string()))
+    }
+}
+
+// A task of the load panicked or was aborted
+impl From<JoinError> for LoadError {
+    fn from(error: JoinError) -> Self {
+        let message = match error.try_into_panic() {
+            Ok(payload) => panic_message(payload),
+            Err(error) => error.to_string(),
+        };
+        LoadError::new(
+            ErrorKind::Internal,
+            format!("Computation failed: {}", message),
+        )
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "task panicked".to_string(),
+        },
+    }
+}
+
+impl From<GraphLoaderError> for LoadError {
+    fn from(error: GraphLoaderError) -> Self {
+        let kind = match &error {
+            GraphLoaderError::RequestError(reqwest_middleware::Error::Reqwest(e))
+                if e.is_timeout() =>
+            {
+                ErrorKind::Timeout
+            }
+            GraphLoaderError::RequestError(_)
+            | GraphLoaderError::RequestBuilderError(_)
+            | GraphLoaderError::TlsCertError(_)
+            | GraphLoaderError::NoDatabaseServers => ErrorKind::Connection,
+            GraphLoaderError::ArangoDBError(_, _, status)
+            | GraphLoaderError::InvalidStatusCode(status)
+                if is_authentication_status(*status) =>
+            {
+                ErrorKind::Authentication
+            }
+            GraphLoaderError::ArangoDBError(ERROR_ARANGO_DATA_SOURCE_NOT_FOUND, _, _) => {
+                ErrorKind::CollectionNotFound { collection: None }
+            }
+            GraphLoaderError::Other(message) => kind_of_flattened(message),
+            _ => ErrorKind::Other,
+        };
+        LoadError::new(kind, error.to_string())
+    }
+}
+
+fn is_authentication_status(status: StatusCode) -> bool {
+    status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
+}
+
+// Errors of the exporter keep their variants, except for a few it only has
+// a message for. Of those, a collection missing from the shard distribution
+// of a cluster is told apart here.
+fn kind_of_flattened(message: &str) -> ErrorKind {
+    if message.ends_with(" not found in shard distribution") {
+        ErrorKind::CollectionNotFound { collection: None }
+    } else {
+        ErrorKind::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(error: GraphLoaderError) -> ErrorKind {
+        LoadError::from(error).kind
+    }
+
+    #[test]
+    fn exporter_errors_are_mapped_by_variant() {
+        let request_error = reqwest::Client::new().get("no url").build().unwrap_err();
+        assert_eq!(
+            kind_of(GraphLoaderError::RequestError(request_error.into())),
+            ErrorKind::Connection
+        );
+        assert_eq!(
+            kind_of(GraphLoaderError::ArangoDBError(
+                11,
+                "not authorized".to_string(),
+                StatusCode::UNAUTHORIZED
+            )),
+            ErrorKind::Authentication
+        );
+        assert_eq!(
+            kind_of(GraphLoaderError::InvalidStatusCode(StatusCode::FORBIDDEN)),
+            ErrorKind::Authentication
+        );
+        assert_eq!(
+            kind_of(GraphLoaderError::ArangoDBError(
+                ERROR_ARANGO_DATA_SOURCE_NOT_FOUND,
+                "collection or view not found: person".to_string(),
+                StatusCode::NOT_FOUND
+            )),
+            ErrorKind::CollectionNotFound { collection: None }
+        );
+        assert_eq!(
+            kind_of(GraphLoaderError::ArangoDBError(
+                1200,
+                "conflict".to_string(),
+                StatusCode::CONFLICT
+            )),
+            ErrorKind::Other
+        );
+    }
+
+    // Pins the message of the exporter's compute_shard_map
+    #[test]
+    fn collection_missing_from_shard_distribution_is_not_found() {
+        assert_eq!(
+            kind_of(GraphLoaderError::from(
+                "collection person not found in shard distribution".to_string()
+            )),
+            ErrorKind::CollectionNotFound { collection: None }
+        );
+        assert_eq!(
+            kind_of(GraphLoaderError::from("Load strategy not set".to_string())),
+            ErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn panicked_task_is_an_internal_error_with_its_message() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let error = runtime
+            .block_on(runtime.spawn(async { panic!("shard {} is gone", 3) }))
+            .unwrap_err();
+        let error = LoadError::from(error);
+        assert_eq!(error.kind, ErrorKind::Internal);
+        assert_eq!(error.message, "Computation failed: shard 3 is gone");
+    }
+
+    #[test]
+    fn graph_errors_keep_a_wrapped_load_error() {
+        let schema_error = LoadError::schema(Some("person"), None, Some("age"), "not numeric");
+        let error = LoadError::from(anyhow::Error::new(schema_error.clone()));
+        assert_eq!(error.kind, schema_error.kind);
+
+        let error = LoadError::from(anyhow::anyhow!("something else"));
+        assert_eq!(error.kind, ErrorKind::Other);
+        assert_eq!(error.message, "something else");
+    }
+}
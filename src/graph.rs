@@ -1,23 +1,117 @@
-use serde_json::{Map, Value};
-use std::collections::HashMap;
-use std::hash::Hash;
+use crate::error::{ErrorKind, LoadError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
 use std::sync::{Arc, RwLock};
 
 use anyhow::{anyhow, Result};
 use log::warn;
 
-#[derive(Eq, Hash, PartialEq, Clone, Copy, Ord, PartialOrd, Debug)]
-pub struct VertexHash(u64);
+// e.g {'node/2': {'weight': 1}, ...}
+pub type GraphAdjMap = HashMap<String, HashMap<String, Map<String, Value>>>;
+// e.g {'succ': GraphAdjMap, 'pred': GraphAdjMap}
+pub type DiGraphAdjMap = HashMap<String, GraphAdjMap>;
+// e.g {'node/1': {'node/2': {0: {'weight': 1}, 1: {...}}}, ...}
+pub type MultiGraphAdjMap = HashMap<String, HashMap<String, HashMap<usize, Map<String, Value>>>>;
+// e.g {'succ': MultiGraphAdjMap, 'pred': MultiGraphAdjMap}
+pub type MultiDiGraphAdjMap = HashMap<String, MultiGraphAdjMap>;
+
+type GetVertexPropertiesFn =
+    fn(&NetworkXGraph, String, Vec<Value>, &[String]) -> Result<Map<String, Value>>;
+type GetEdgePropertiesFn =
+    fn(&NetworkXGraph, String, String, Vec<Value>, &[String]) -> Result<Map<String, Value>>;
+type InsertCooFn = fn(&mut NetworkXGraph, String, String, HashMap<String, f64>);
+type InsertAdjFn = fn(&mut NetworkXGraph, String, String, Map<String, Value>) -> Result<()>;
+type ShardEdgeFn = fn(&NetworkXGraph, String, String, Vec<Value>, &[String]) -> Result<ShardedEdge>;
+
+fn fail_if_edge_exists<X>(
+    map: &HashMap<String, X>,
+    from_id_str: String,
+    to_id_str: String,
+) -> Result<()> {
+    if map.contains_key(&to_id_str) {
+        let message = format!("Edge '{}' to '{}' already exists in Adjacency Dictionary. Consider switching to Multi(Di)Graph instead.", from_id_str, to_id_str);
+        let kind = ErrorKind::DuplicateEdge {
+            from_id: from_id_str,
+            to_id: to_id_str,
+        };
+        return Err(LoadError::new(kind, message).into());
+    }
+    Ok(())
+}
 
-#[derive(Eq, PartialEq, Clone, Copy, Ord, PartialOrd, Debug)]
-pub struct VertexIndex(u64);
+fn schema_error(
+    collection: Option<&str>,
+    document_id: Option<&str>,
+    attribute: Option<&str>,
+    message: String,
+) -> anyhow::Error {
+    LoadError::schema(collection, document_id, attribute, message).into()
+}
 
-fn panic_if_edge_exists<X>(map: &HashMap<String, X>, from_id_str: String, to_id_str: String) {
-    if map.contains_key(&to_id_str) {
-        panic!("ERROR: Edge '{}' to '{}' already exists in Adjacency Dictionary. Consider switching to Multi(Di)Graph instead.", from_id_str, to_id_str);
+// Ids arrive as bytes, `attribute` is the one the id was read from
+fn parse_id(id: Vec<u8>, attribute: &str) -> Result<String> {
+    String::from_utf8(id).map_err(|e| {
+        let id = String::from_utf8_lossy(e.as_bytes()).into_owned();
+        let message = format!("{} '{}' is not valid UTF-8", attribute, id);
+        schema_error(None, Some(&id), Some(attribute), message)
+    })
+}
+
+// Splits e.g. `person/1` into `person` and `1`
+fn split_id(
+    id: &str,
+    attribute: &str,
+    edge_id: Option<&str>,
+    edge: &str,
+) -> Result<(String, String)> {
+    match id.split_once('/') {
+        Some((collection, key)) => Ok((collection.to_string(), key.to_string())),
+        None => {
+            let message = format!("Invalid format for {} of {}", attribute, edge);
+            Err(schema_error(None, edge_id, Some(attribute), message))
+        }
     }
 }
 
+// The collection of the document `document_id`, described in errors as `document`
+fn collection_name<'a>(
+    columns: &'a [Value],
+    field_names: &[String],
+    document_id: Option<&str>,
+    document: &str,
+) -> Result<&'a str> {
+    let attribute = "@collection_name";
+    let position = field_names
+        .iter()
+        .position(|x| x == attribute)
+        .ok_or_else(|| {
+            let message = format!("No {} in field names of {}", attribute, document);
+            schema_error(None, document_id, Some(attribute), message)
+        })?;
+    match &columns[position] {
+        Value::String(s) => Ok(s),
+        _ => {
+            let message = format!("Expected a string for {} of {}", attribute, document);
+            Err(schema_error(None, document_id, Some(attribute), message))
+        }
+    }
+}
+
+// Edge batches carry the edge's own id only if it was requested
+fn edge_document_id<'a>(columns: &'a [Value], field_names: &[String]) -> Option<&'a str> {
+    match field_names.iter().position(|x| x == "_id") {
+        Some(position) => columns[position].as_str(),
+        None if field_names.is_empty() => columns.first()?.get("_id")?.as_str(),
+        None => None,
+    }
+}
+
+fn describe_edge(from_id: &str, to_id: &str) -> String {
+    format!("the edge from '{}' to '{}'", from_id, to_id)
+}
+
 fn parse_value_to_vec(val: &Value) -> Option<Vec<f64>> {
     match val.as_array() {
         Some(v) => {
@@ -32,34 +126,243 @@ fn parse_value_to_vec(val: &Value) -> Option<Vec<f64>> {
     }
 }
 
+// Collection, key and numeric features of a vertex
+type VertexFeatures = (String, String, HashMap<String, Vec<f64>>);
+
+// None if the vertex has no features and is left out of the numpy graph
+pub fn parse_vertex_features(
+    id: Vec<u8>,
+    columns: &[Value],
+    field_names: &[String],
+) -> Result<Option<VertexFeatures>> {
+    debug_assert!(!columns.is_empty());
+    debug_assert_eq!(columns.len(), field_names.len());
+
+    let cur_id_str = parse_id(id, "_id")?;
+    let vertex = format!("vertex '{}'", cur_id_str);
+    let col_name = collection_name(columns, field_names, Some(&cur_id_str), &vertex)?;
+
+    let mut feature_res: HashMap<String, Vec<f64>> = HashMap::new();
+    for (i, feature_name) in field_names.iter().enumerate() {
+        if feature_name == "_id" {
+            continue;
+        }
+        let feature_vec = parse_value_to_vec(&columns[i]);
+        if feature_vec.is_none() {
+            continue;
+        }
+        feature_res.insert(feature_name.clone(), feature_vec.unwrap());
+    }
+
+    if feature_res.is_empty() {
+        return Ok(None);
+    }
+    // let cur_key_str = cur_id_str.splitn(2, '/').nth(1).unwrap().to_string();
+    // This is a bit stupid right now. Before the library merge of lightning, this route here
+    // always ad the id here in key.clone(). Now it is not the case anymore. So we need to
+    // check if the key is already in the format of the id or not. This should be done better soon.
+    // This only occurs in case we're using the AQL Load variant.
+    let cur_key_str = cur_id_str.split_once('/').map_or_else(
+        || cur_id_str.clone(),      // If no '/', use the whole string
+        |(_, key)| key.to_string(), // If '/' is present, use the part after '/'
+    );
+    Ok(Some((col_name.to_string(), cur_key_str, feature_res)))
+}
+
+// Whether a requested edge attribute can be a feature, i.e. is not one the
+// loader adds to identify the edge
+pub fn is_edge_feature_name(name: &str) -> bool {
+    name != "_id" && name != "@collection_name"
+}
+
+// Numeric features of an edge, missing and non-numeric values are left out
+pub fn parse_edge_features(columns: &[Value], field_names: &[String]) -> HashMap<String, Vec<f64>> {
+    field_names
+        .iter()
+        .zip(columns)
+        .filter(|(name, _)| is_edge_feature_name(name))
+        .filter_map(|(name, value)| parse_value_to_vec(value).map(|v| (name.clone(), v)))
+        .collect()
+}
+
+// Rows of edges without a value are empty until all edges are merged, then
+// they are filled with NaN, as wide as the rows of the other edges
+pub fn fill_missing_edge_features(
+    key_tup: &(String, String, String),
+    features: &mut HashMap<String, Vec<Vec<f64>>>,
+    edge_count: usize,
+) -> Result<()> {
+    for (feature_name, rows) in features.iter_mut() {
+        rows.resize(edge_count, vec![]);
+        let width = rows.iter().map(Vec::len).find(|len| *len > 0).unwrap_or(0);
+        for row in rows.iter_mut() {
+            if row.is_empty() {
+                *row = vec![f64::NAN; width];
+            } else if row.len() != width {
+                return Err(anyhow!(
+                    "Edge attribute {} of {:?} has vectors of different lengths ({} and {})",
+                    feature_name,
+                    key_tup,
+                    width,
+                    row.len()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// The (edge, from, to) triple of an edge and the indices of its vertices
+type ResolvedEdge = ((String, String, String), Option<(usize, usize)>);
+
+// None if a vertex collection is not part of the graph, the indices are None
+// if one of the vertices was not loaded
+pub fn resolve_edge_indices(
+    cols_to_keys_to_inds: &HashMap<String, HashMap<String, usize>>,
+    from_id: Vec<u8>,
+    to_id: Vec<u8>,
+    columns: &[Value],
+    field_names: &[String],
+) -> Result<Option<ResolvedEdge>> {
+    debug_assert!(!columns.is_empty());
+    debug_assert_eq!(columns.len(), field_names.len());
+
+    let from_id_str = parse_id(from_id, "_from")?;
+    let to_id_str = parse_id(to_id, "_to")?;
+    let edge_id = edge_document_id(columns, field_names);
+    let edge = describe_edge(&from_id_str, &to_id_str);
+    let (from_col, from_key) = split_id(&from_id_str, "_from", edge_id, &edge)?;
+    let (to_col, to_key) = split_id(&to_id_str, "_to", edge_id, &edge)?;
+
+    // if either from_col or to_col is not part of the metagraph definition,
+    // we will not add it as an edge
+    if !cols_to_keys_to_inds.contains_key(&from_col) {
+        warn!(
+            "Skipping edge from {} to {} as {} is not part of the metagraph",
+            from_col, to_col, from_col
+        );
+        return Ok(None);
+    }
+    if !cols_to_keys_to_inds.contains_key(&to_col) {
+        warn!(
+            "Skipping edge from {} to {} as {} is not part of the metagraph",
+            from_col, to_col, to_col
+        );
+        return Ok(None);
+    }
+
+    let col_name = collection_name(columns, field_names, edge_id, &edge)?;
+
+    let from_col_keys = cols_to_keys_to_inds
+        .get(&from_col)
+        .ok_or_else(|| anyhow!("Unable to get keys `from` for {:?}", &from_col))?;
+    let to_col_keys = cols_to_keys_to_inds
+        .get(&to_col)
+        .ok_or_else(|| anyhow!("Unable to get keys `to` for {:?}", &to_col))?;
+    let indices = match (from_col_keys.get(&from_key), to_col_keys.get(&to_key)) {
+        (Some(from_id), Some(to_id)) => Some((*from_id, *to_id)),
+        _ => None,
+    };
+    Ok(Some(((col_name.to_string(), from_col, to_col), indices)))
+}
+
+// Batches are inserted into shards, one per stream of the source, while the
+// graph itself is only read. Once all vertices (or edges) are in, the shards
+// are merged into the graph in stream order, which is also the order indices
+// are assigned in.
 pub trait Graph {
+    type VertexShard: Default + Send;
+    type EdgeShard: Default + Send;
+
     fn insert_vertex(
-        &mut self,
-        id: Vec<u8>,               // cannot be empty
+        &self,
+        shard: &mut Self::VertexShard,
+        id: Vec<u8>,            // cannot be empty
         columns: Vec<Value>, // columns is either with load_all_vertex_attributes set to True or False
-        field_names: &Vec<String>, // should be empty if load_all_vertex_attributes is set to True
-    );
+        field_names: &[String], // should be empty if load_all_vertex_attributes is set to True
+    ) -> anyhow::Result<()>;
 
+    fn merge_vertices(&mut self, shards: Vec<Self::VertexShard>) -> anyhow::Result<()>;
+
+    // Called after `merge_vertices`, so edges can be resolved against all vertices
     fn insert_edge(
-        &mut self,
+        &self,
+        shard: &mut Self::EdgeShard,
         from_id: Vec<u8>,
         to_id: Vec<u8>,
         columns: Vec<Value>, // columns is either with load_all_edge_attributes set to True or False (for now, False case is not supported)
-        field_names: &Vec<String>, // should be empty if load_all_edge_attributes is set to True
+        field_names: &[String], // should be empty if load_all_edge_attributes is set to True
     ) -> anyhow::Result<()>;
+
+    fn merge_edges(&mut self, shards: Vec<Self::EdgeShard>) -> anyhow::Result<()>;
+
+    // Only graphs which can be persisted are cached
+    fn persist(&self) -> Option<&dyn Persist> {
+        None
+    }
+
+    fn persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        None
+    }
 }
 
-#[derive(Debug)]
+// Graphs whose result can be written to a file and read back, for the result
+// cache and snapshots
+pub trait Persist {
+    // How the graph is built, results of graphs built differently differ
+    // even for the same request
+    fn describe(&self) -> Value;
+
+    // Writes what the load built, without how the graph was set up
+    fn save_data(&self, writer: &mut dyn Write) -> anyhow::Result<()>;
+
+    // Reads what `save_data` wrote into a graph described the same way
+    fn restore_data(&mut self, reader: &mut dyn Read) -> anyhow::Result<()>;
+}
+
+// Feature rows of the edges of each (edge, from, to) triple
+pub type EdgeFeatureMap = HashMap<(String, String, String), HashMap<String, Vec<Vec<f64>>>>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct NumpyGraph {
     pub cols_to_keys_to_inds: HashMap<String, HashMap<String, usize>>,
     pub cols_to_inds_to_keys: HashMap<String, HashMap<usize, String>>,
     pub coo_by_from_edge_to: HashMap<(String, String, String), Vec<Vec<usize>>>,
     pub cols_to_features: HashMap<String, HashMap<String, Vec<Vec<f64>>>>,
+    // Numeric edge attributes, row `i` belongs to column `i` of the COO
+    // arrays of the same (edge, from, to) triple
+    #[serde(default)]
+    pub coo_features_by_from_edge_to: EdgeFeatureMap,
+}
+
+// Vertices of one stream, per collection in insertion order
+#[derive(Default)]
+pub struct NumpyVertexShard {
+    pub cols_to_keys: HashMap<String, Vec<String>>,
+    cols_to_features: HashMap<String, HashMap<String, Vec<Vec<f64>>>>,
+}
+
+// Edges of one stream, already resolved to vertex indices
+#[derive(Default)]
+pub struct NumpyEdgeShard {
+    coo_by_from_edge_to: HashMap<(String, String, String), Vec<Vec<usize>>>,
+    coo_features_by_from_edge_to: EdgeFeatureMap,
+}
+
+// An edge with its properties extracted, waiting to be merged. Indices and
+// adjacency depend on all edges before it, so they are only built then.
+pub struct ShardedEdge {
+    from_id: String,
+    to_id: String,
+    coo_properties: Option<HashMap<String, f64>>,
+    adj_properties: Option<Map<String, Value>>,
 }
 
 #[derive(Debug)]
 pub struct NetworkXGraph {
     pub symmetrize_edges_if_directed: bool,
+    // The options the graph was created with
+    description: Value,
 
     // node_map is a dictionary of node IDs to their json data
     // e.g {'user/1': {'name': 'Alice', 'age': 25}, 'user/2': {'name': 'Bob', 'age': 30}, ...}
@@ -67,11 +370,10 @@ pub struct NetworkXGraph {
 
     // adj_map represents the adjacency list of the graph
     // it can be a graph, digraph, multigraph, or multidigraph
-    pub adj_map_graph: HashMap<String, HashMap<String, Map<String, Value>>>,
-    pub adj_map_digraph: HashMap<String, HashMap<String, HashMap<String, Map<String, Value>>>>,
-    pub adj_map_multigraph: HashMap<String, HashMap<String, HashMap<usize, Map<String, Value>>>>,
-    pub adj_map_multidigraph:
-        HashMap<String, HashMap<String, HashMap<String, HashMap<usize, Map<String, Value>>>>>,
+    pub adj_map_graph: GraphAdjMap,
+    pub adj_map_digraph: DiGraphAdjMap,
+    pub adj_map_multigraph: MultiGraphAdjMap,
+    pub adj_map_multidigraph: MultiDiGraphAdjMap,
 
     pub coo: (Vec<usize>, Vec<usize>), // e.g ([0, 1, 2], [1, 2, 3])
     pub vertex_id_to_index: HashMap<String, usize>, // e.g {'user/1': 0, 'user/2': 1, ...}
@@ -80,13 +382,11 @@ pub struct NetworkXGraph {
     pub edge_values: HashMap<String, Vec<f64>>, // {'weight': [4, 5, 1], ...)}
 
     // pre-defined functions
-    get_vertex_properties_fn:
-        fn(&mut NetworkXGraph, String, Vec<Value>, &Vec<String>) -> Map<String, Value>,
-    get_edge_properties_fn:
-        fn(&mut NetworkXGraph, String, String, Vec<Value>, &Vec<String>) -> Map<String, Value>,
-    insert_coo_fn: fn(&mut NetworkXGraph, String, String, HashMap<String, f64>),
-    insert_adj_fn: fn(&mut NetworkXGraph, String, String, Map<String, Value>),
-    insert_edge_fn: fn(&mut NetworkXGraph, String, String, Vec<Value>, &Vec<String>) -> Result<()>,
+    get_vertex_properties_fn: GetVertexPropertiesFn,
+    get_edge_properties_fn: GetEdgePropertiesFn,
+    insert_coo_fn: InsertCooFn,
+    insert_adj_fn: InsertAdjFn,
+    shard_edge_fn: ShardEdgeFn,
 }
 
 impl NumpyGraph {
@@ -96,6 +396,7 @@ impl NumpyGraph {
             cols_to_keys_to_inds: HashMap::new(),
             cols_to_inds_to_keys: HashMap::new(),
             coo_by_from_edge_to: HashMap::new(),
+            coo_features_by_from_edge_to: HashMap::new(),
         }))
     }
 }
@@ -166,21 +467,33 @@ impl NetworkXGraph {
             }
         };
 
-        let insert_edge_fn = if load_coo && load_adj_dict {
-            NetworkXGraph::insert_edge_as_coo_and_adj
+        let shard_edge_fn = if load_coo && load_adj_dict {
+            NetworkXGraph::shard_edge_as_coo_and_adj
         } else if load_coo {
-            NetworkXGraph::insert_edge_as_coo_only
+            NetworkXGraph::shard_edge_as_coo_only
         } else {
-            NetworkXGraph::insert_edge_as_adj_only
+            NetworkXGraph::shard_edge_as_adj_only
         };
 
+        let description = json!({
+            "graph": "networkx",
+            "load_adj_dict": load_adj_dict,
+            "load_coo": load_coo,
+            "load_all_vertex_attributes": load_all_vertex_attributes,
+            "load_all_edge_attributes": load_all_edge_attributes,
+            "is_directed": is_directed,
+            "is_multigraph": is_multigraph,
+            "symmetrize_edges_if_directed": symmetrize_edges_if_directed,
+        });
+
         Arc::new(RwLock::new(NetworkXGraph {
             symmetrize_edges_if_directed,
+            description,
             node_map: HashMap::new(),
             adj_map_graph: HashMap::new(),
-            adj_map_digraph: adj_map_digraph,
+            adj_map_digraph,
             adj_map_multigraph: HashMap::new(),
-            adj_map_multidigraph: adj_map_multidigraph,
+            adj_map_multidigraph,
             coo: (vec![], vec![]),
             vertex_id_to_index: HashMap::new(),
             edge_indices: vec![],
@@ -190,36 +503,40 @@ impl NetworkXGraph {
             get_edge_properties_fn,
             insert_coo_fn,
             insert_adj_fn,
-            insert_edge_fn,
+            shard_edge_fn,
         }))
     }
 
     fn get_vertex_properties_all(
-        &mut self,
+        &self,
         vertex_id: String,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Map<String, Value> {
+        field_names: &[String],
+    ) -> Result<Map<String, Value>> {
         debug_assert_eq!(columns.len(), 1);
         debug_assert_eq!(field_names.len(), 0);
 
         let json = columns.first();
         let mut properties = match json {
             Some(Value::Object(map)) => map.clone(),
-            _ => panic!("Vertex data must be a json object"),
+            _ => {
+                let message = format!("Vertex data of '{}' must be a json object", vertex_id);
+                let collection = vertex_id.split_once('/').map(|(collection, _)| collection);
+                return Err(schema_error(collection, Some(&vertex_id), None, message));
+            }
         };
 
         properties.insert("_id".to_string(), Value::String(vertex_id.clone()));
 
-        properties
+        Ok(properties)
     }
 
     fn get_vertex_properties_selected(
-        &mut self,
+        &self,
         _vertex_id: String,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Map<String, Value> {
+        field_names: &[String],
+    ) -> Result<Map<String, Value>> {
         let mut properties = Map::new();
 
         for (i, field_name) in field_names.iter().enumerate() {
@@ -234,38 +551,42 @@ impl NetworkXGraph {
             properties.insert(field_name.clone(), columns[i].clone());
         }
 
-        properties
+        Ok(properties)
     }
 
     fn get_edge_properties_all(
-        &mut self,
+        &self,
         from_id: String,
         to_id: String,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Map<String, Value> {
+        field_names: &[String],
+    ) -> Result<Map<String, Value>> {
         debug_assert_eq!(columns.len(), 1);
         debug_assert_eq!(field_names.len(), 0);
 
         let json = columns.first();
         let mut properties = match json {
             Some(Value::Object(map)) => map.clone(),
-            _ => panic!("Edge data must be a json object"),
+            _ => {
+                let edge = describe_edge(&from_id, &to_id);
+                let message = format!("Edge data of {} must be a json object", edge);
+                return Err(schema_error(None, None, None, message));
+            }
         };
 
         properties.insert("_from".to_string(), Value::String(from_id.clone()));
         properties.insert("_to".to_string(), Value::String(to_id.clone()));
 
-        properties
+        Ok(properties)
     }
 
     fn get_edge_properties_selected(
-        &mut self,
+        &self,
         _from_id: String,
         _to_id: String,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Map<String, Value> {
+        field_names: &[String],
+    ) -> Result<Map<String, Value>> {
         let mut properties = Map::new();
 
         for (i, field_name) in field_names.iter().enumerate() {
@@ -280,7 +601,7 @@ impl NetworkXGraph {
             properties.insert(field_name.clone(), columns[i].clone());
         }
 
-        properties
+        Ok(properties)
     }
 
     fn get_from_and_to_id_index(
@@ -425,7 +746,7 @@ impl NetworkXGraph {
         from_id_str: String,
         to_id_str: String,
         properties: Map<String, Value>,
-    ) {
+    ) -> Result<()> {
         if !self.adj_map_graph.contains_key(&from_id_str) {
             self.adj_map_graph
                 .insert(from_id_str.clone(), HashMap::new());
@@ -436,15 +757,16 @@ impl NetworkXGraph {
         }
 
         let from_map = self.adj_map_graph.get_mut(&from_id_str).unwrap();
-        panic_if_edge_exists(from_map, from_id_str.clone(), to_id_str.clone());
+        fail_if_edge_exists(from_map, from_id_str.clone(), to_id_str.clone())?;
         from_map.insert(to_id_str.clone(), properties.clone());
 
         let to_map = self.adj_map_graph.get_mut(&to_id_str).unwrap();
         if from_id_str != to_id_str {
-            panic_if_edge_exists(to_map, to_id_str, from_id_str.clone());
+            fail_if_edge_exists(to_map, to_id_str, from_id_str.clone())?;
         }
 
         to_map.insert(from_id_str, properties);
+        Ok(())
     }
 
     fn insert_adj_digraph(
@@ -452,7 +774,7 @@ impl NetworkXGraph {
         from_id_str: String,
         to_id_str: String,
         properties: Map<String, Value>,
-    ) {
+    ) -> Result<()> {
         // 1) Add [from, to] in _succ adjacency list
         let _succ = self.adj_map_digraph.get_mut("succ").unwrap();
 
@@ -465,13 +787,13 @@ impl NetworkXGraph {
         }
 
         let succ_from_map = _succ.get_mut(&from_id_str).unwrap();
-        panic_if_edge_exists(succ_from_map, from_id_str.clone(), to_id_str.clone());
+        fail_if_edge_exists(succ_from_map, from_id_str.clone(), to_id_str.clone())?;
         succ_from_map.insert(to_id_str.clone(), properties.clone());
 
         if self.symmetrize_edges_if_directed {
             let succ_to_map = _succ.get_mut(&to_id_str).unwrap();
             if from_id_str != to_id_str {
-                panic_if_edge_exists(succ_to_map, to_id_str.clone(), from_id_str.clone());
+                fail_if_edge_exists(succ_to_map, to_id_str.clone(), from_id_str.clone())?;
             }
             succ_to_map.insert(from_id_str.clone(), properties.clone());
         }
@@ -488,16 +810,17 @@ impl NetworkXGraph {
         }
 
         let pred_to_map = _pred.get_mut(&to_id_str).unwrap();
-        panic_if_edge_exists(pred_to_map, to_id_str.clone(), from_id_str.clone());
+        fail_if_edge_exists(pred_to_map, to_id_str.clone(), from_id_str.clone())?;
         pred_to_map.insert(from_id_str.clone(), properties.clone());
 
         if self.symmetrize_edges_if_directed {
             let pred_from_map = _pred.get_mut(&from_id_str).unwrap();
             if from_id_str != to_id_str {
-                panic_if_edge_exists(pred_from_map, from_id_str, to_id_str.clone());
+                fail_if_edge_exists(pred_from_map, from_id_str, to_id_str.clone())?;
             }
             pred_from_map.insert(to_id_str, properties);
         }
+        Ok(())
     }
 
     fn insert_adj_multigraph(
@@ -505,7 +828,7 @@ impl NetworkXGraph {
         from_id_str: String,
         to_id_str: String,
         properties: Map<String, Value>,
-    ) {
+    ) -> Result<()> {
         if !self.adj_map_multigraph.contains_key(&from_id_str) {
             self.adj_map_multigraph
                 .insert(from_id_str.clone(), HashMap::new());
@@ -524,6 +847,7 @@ impl NetworkXGraph {
         let to_map = self.adj_map_multigraph.get_mut(&to_id_str).unwrap();
         let to_from_map = to_map.entry(from_id_str).or_default();
         to_from_map.insert(index, properties);
+        Ok(())
     }
 
     fn insert_adj_multidigraph(
@@ -531,7 +855,7 @@ impl NetworkXGraph {
         from_id_str: String,
         to_id_str: String,
         properties: Map<String, Value>,
-    ) {
+    ) -> Result<()> {
         // 1) Add [from, to] in _succ adjacency list
         let _succ = self.adj_map_multidigraph.get_mut("succ").unwrap();
 
@@ -576,15 +900,16 @@ impl NetworkXGraph {
             let pred_from_to_map = pred_from_map.entry(to_id_str).or_default();
             pred_from_to_map.insert(index, properties);
         }
+        Ok(())
     }
 
-    fn insert_edge_as_coo(
-        &mut self,
-        from_id_str: String,
-        to_id_str: String,
-        columns: &Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Result<()> {
+    fn get_coo_properties(
+        &self,
+        from_id_str: &str,
+        to_id_str: &str,
+        columns: &[Value],
+        field_names: &[String],
+    ) -> Result<HashMap<String, f64>> {
         let mut properties: HashMap<String, f64> = HashMap::new();
         for (field_position, field_name) in field_names.iter().enumerate() {
             if field_name == "@collection_name" {
@@ -592,281 +917,580 @@ impl NetworkXGraph {
             }
             let field_vec = match columns[field_position].as_f64() {
                 Some(v) => v,
-                _ => return Err(anyhow!("Edge data must be a numeric value")),
+                _ => {
+                    let collection = field_names
+                        .iter()
+                        .position(|name| name == "@collection_name")
+                        .and_then(|position| columns[position].as_str());
+                    let message = format!(
+                        "Edge data must be a numeric value, got {} for '{}' of {}",
+                        columns[field_position],
+                        field_name,
+                        describe_edge(from_id_str, to_id_str)
+                    );
+                    return Err(schema_error(
+                        collection,
+                        edge_document_id(columns, field_names),
+                        Some(field_name),
+                        message,
+                    ));
+                }
             };
 
             properties.insert(field_name.clone(), field_vec);
         }
 
-        (self.insert_coo_fn)(self, from_id_str, to_id_str, properties);
-
-        Ok(())
+        Ok(properties)
     }
 
-    fn insert_edge_as_adj(
-        &mut self,
+    fn shard_edge_as_coo_and_adj(
+        &self,
         from_id_str: String,
         to_id_str: String,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Result<()> {
-        let properties = (self.get_edge_properties_fn)(
+        field_names: &[String],
+    ) -> Result<ShardedEdge> {
+        let coo_properties =
+            self.get_coo_properties(&from_id_str, &to_id_str, &columns, field_names)?;
+        let adj_properties = (self.get_edge_properties_fn)(
             self,
             from_id_str.clone(),
             to_id_str.clone(),
             columns,
             field_names,
-        );
-
-        (self.insert_adj_fn)(self, from_id_str, to_id_str, properties);
+        )?;
+
+        Ok(ShardedEdge {
+            from_id: from_id_str,
+            to_id: to_id_str,
+            coo_properties: Some(coo_properties),
+            adj_properties: Some(adj_properties),
+        })
+    }
 
-        Ok(())
+    fn shard_edge_as_coo_only(
+        &self,
+        from_id_str: String,
+        to_id_str: String,
+        columns: Vec<Value>,
+        field_names: &[String],
+    ) -> Result<ShardedEdge> {
+        let coo_properties =
+            self.get_coo_properties(&from_id_str, &to_id_str, &columns, field_names)?;
+
+        Ok(ShardedEdge {
+            from_id: from_id_str,
+            to_id: to_id_str,
+            coo_properties: Some(coo_properties),
+            adj_properties: None,
+        })
     }
 
-    fn insert_edge_as_coo_and_adj(
-        &mut self,
+    fn shard_edge_as_adj_only(
+        &self,
         from_id_str: String,
         to_id_str: String,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Result<()> {
-        let res = self.insert_edge_as_coo(
+        field_names: &[String],
+    ) -> Result<ShardedEdge> {
+        let adj_properties = (self.get_edge_properties_fn)(
+            self,
             from_id_str.clone(),
             to_id_str.clone(),
-            &columns,
+            columns,
             field_names,
-        );
+        )?;
+
+        Ok(ShardedEdge {
+            from_id: from_id_str,
+            to_id: to_id_str,
+            coo_properties: None,
+            adj_properties: Some(adj_properties),
+        })
+    }
+}
 
-        if let Err(e) = res {
-            return Err(e);
-        }
+impl Graph for NumpyGraph {
+    type VertexShard = NumpyVertexShard;
+    type EdgeShard = NumpyEdgeShard;
 
-        self.insert_edge_as_adj(from_id_str, to_id_str, columns, field_names)?;
+    fn insert_vertex(
+        &self,
+        shard: &mut NumpyVertexShard,
+        id: Vec<u8>, // cannot be empty
+        columns: Vec<Value>,
+        field_names: &[String],
+    ) -> Result<()> {
+        let Some((col_name, cur_key_str, feature_res)) =
+            parse_vertex_features(id, &columns, field_names)?
+        else {
+            return Ok(());
+        };
 
+        shard
+            .cols_to_keys
+            .entry(col_name.clone())
+            .or_default()
+            .push(cur_key_str);
+
+        let current_col_to_feats = shard.cols_to_features.entry(col_name).or_default();
+        for (feature_name, feature_vec) in feature_res {
+            current_col_to_feats
+                .entry(feature_name)
+                .or_default()
+                .push(feature_vec);
+        }
         Ok(())
     }
 
-    fn insert_edge_as_coo_only(
-        &mut self,
-        from_id_str: String,
-        to_id_str: String,
+    fn merge_vertices(&mut self, shards: Vec<NumpyVertexShard>) -> Result<()> {
+        for shard in shards {
+            for (col_name, keys) in shard.cols_to_keys {
+                let keys_to_inds = self
+                    .cols_to_keys_to_inds
+                    .entry(col_name.clone())
+                    .or_default();
+                let inds_to_keys = self.cols_to_inds_to_keys.entry(col_name).or_default();
+                for key in keys {
+                    let cur_ind = keys_to_inds.len();
+                    keys_to_inds.insert(key.clone(), cur_ind);
+                    inds_to_keys.insert(cur_ind, key);
+                }
+            }
+            for (col_name, features) in shard.cols_to_features {
+                let current_col_to_feats = self.cols_to_features.entry(col_name).or_default();
+                for (feature_name, mut feature_vecs) in features {
+                    current_col_to_feats
+                        .entry(feature_name)
+                        .or_default()
+                        .append(&mut feature_vecs);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_edge(
+        &self,
+        shard: &mut NumpyEdgeShard,
+        from_id: Vec<u8>,
+        to_id: Vec<u8>,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
+        field_names: &[String],
     ) -> Result<()> {
-        let res = self.insert_edge_as_coo(from_id_str, to_id_str, &columns, field_names);
-        if let Err(e) = res {
-            return Err(e);
+        let Some((key_tup, indices)) = resolve_edge_indices(
+            &self.cols_to_keys_to_inds,
+            from_id,
+            to_id,
+            &columns,
+            field_names,
+        )?
+        else {
+            return Ok(());
+        };
+
+        let cur_coo = shard
+            .coo_by_from_edge_to
+            .entry(key_tup.clone())
+            .or_insert_with(|| vec![vec![], vec![]]);
+        let Some((from_id, to_id)) = indices else {
+            return Ok(());
+        };
+        let edge_index = cur_coo[0].len();
+        cur_coo[0].push(from_id);
+        cur_coo[1].push(to_id);
+
+        let feature_res = parse_edge_features(&columns, field_names);
+        if feature_res.is_empty() {
+            return Ok(());
+        }
+        let current_edge_to_feats = shard
+            .coo_features_by_from_edge_to
+            .entry(key_tup)
+            .or_default();
+        for (feature_name, feature_vec) in feature_res {
+            let rows = current_edge_to_feats.entry(feature_name).or_default();
+            rows.resize(edge_index, vec![]);
+            rows.push(feature_vec);
         }
+        Ok(())
+    }
 
+    fn merge_edges(&mut self, shards: Vec<NumpyEdgeShard>) -> Result<()> {
+        for mut shard in shards {
+            for (key_tup, coo) in shard.coo_by_from_edge_to {
+                let cur_coo = self
+                    .coo_by_from_edge_to
+                    .entry(key_tup.clone())
+                    .or_insert_with(|| vec![vec![], vec![]]);
+                // The rows of this shard start after the edges merged so far
+                let edge_offset = cur_coo[0].len();
+                for (cur, mut new) in cur_coo.iter_mut().zip(coo) {
+                    cur.append(&mut new);
+                }
+                let Some(features) = shard.coo_features_by_from_edge_to.remove(&key_tup) else {
+                    continue;
+                };
+                let current_edge_to_feats = self
+                    .coo_features_by_from_edge_to
+                    .entry(key_tup)
+                    .or_default();
+                for (feature_name, mut feature_vecs) in features {
+                    let rows = current_edge_to_feats.entry(feature_name).or_default();
+                    rows.resize(edge_offset, vec![]);
+                    rows.append(&mut feature_vecs);
+                }
+            }
+        }
+        for (key_tup, features) in self.coo_features_by_from_edge_to.iter_mut() {
+            let edge_count = self.coo_by_from_edge_to[key_tup][0].len();
+            fill_missing_edge_features(key_tup, features, edge_count)?;
+        }
         Ok(())
     }
 
-    fn insert_edge_as_adj_only(
-        &mut self,
-        from_id_str: String,
-        to_id_str: String,
-        columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) -> Result<()> {
-        self.insert_edge_as_adj(from_id_str, to_id_str, columns, field_names)?;
+    fn persist(&self) -> Option<&dyn Persist> {
+        Some(self)
+    }
 
+    fn persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        Some(self)
+    }
+}
+
+impl Persist for NumpyGraph {
+    fn describe(&self) -> Value {
+        json!({"graph": "numpy"})
+    }
+
+    fn save_data(&self, writer: &mut dyn Write) -> Result<()> {
+        rmp_serde::encode::write(writer, self)?;
+        Ok(())
+    }
+
+    fn restore_data(&mut self, reader: &mut dyn Read) -> Result<()> {
+        *self = rmp_serde::decode::from_read(reader)?;
         Ok(())
     }
 }
 
-impl Graph for NumpyGraph {
+impl Graph for NetworkXGraph {
+    type VertexShard = Vec<(String, Map<String, Value>)>;
+    type EdgeShard = Vec<ShardedEdge>;
+
     fn insert_vertex(
-        &mut self,
+        &self,
+        shard: &mut Vec<(String, Map<String, Value>)>,
         id: Vec<u8>, // cannot be empty
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) {
-        debug_assert!(!columns.is_empty());
-        debug_assert_eq!(columns.len(), field_names.len());
-
-        let col_name_position = field_names
-            .iter()
-            .position(|x| x == "@collection_name")
-            .expect("No @collection_name in field names");
-        let col_name = match &columns[col_name_position] {
-            Value::String(s) => s,
-            _ => panic!("Expected Value::String for @collection_name"),
-        };
+        field_names: &[String],
+    ) -> Result<()> {
+        let vertex_id = parse_id(id, "_id")?;
 
-        let mut feature_res: HashMap<String, Vec<f64>> = HashMap::new();
-        for (i, feature_name) in field_names.iter().enumerate() {
-            if feature_name == "_id" {
-                continue;
-            }
-            let feature_vec = parse_value_to_vec(&columns[i]);
-            if feature_vec.is_none() {
-                continue;
-            }
-            feature_res.insert(feature_name.clone(), feature_vec.unwrap());
-        }
-
-        if !feature_res.is_empty() {
-            // insert the vertex
-            if !self.cols_to_keys_to_inds.contains_key(col_name.as_str()) {
-                self.cols_to_keys_to_inds
-                    .insert(col_name.clone(), HashMap::new());
-            }
-
-            if !self.cols_to_inds_to_keys.contains_key(col_name.as_str()) {
-                self.cols_to_inds_to_keys
-                    .insert(col_name.clone(), HashMap::new());
-            }
-
-            let keys_to_inds: &mut HashMap<String, usize> = self
-                .cols_to_keys_to_inds
-                .get_mut(col_name.as_str())
-                .unwrap();
-            let inds_to_keys: &mut HashMap<usize, String> = self
-                .cols_to_inds_to_keys
-                .get_mut(col_name.as_str())
-                .unwrap();
-
-            let cur_ind = keys_to_inds.len();
-            let cur_id_str = String::from_utf8(id.clone()).unwrap();
-            // let cur_key_str = cur_id_str.splitn(2, '/').nth(1).unwrap().to_string();
-            // This is a bit stupid right now. Before the library merge of lightning, this route here
-            // always ad the id here in key.clone(). Now it is not the case anymore. So we need to
-            // check if the key is already in the format of the id or not. This should be done better soon.
-            // This only occurs in case we're using the AQL Load variant.
-            let cur_key_str = cur_id_str.split_once('/').map_or_else(
-                || cur_id_str.clone(),      // If no '/', use the whole string
-                |(_, key)| key.to_string(), // If '/' is present, use the part after '/'
-            );
-
-            keys_to_inds.insert(cur_key_str.clone(), cur_ind);
-            inds_to_keys.insert(cur_ind, cur_key_str);
-
-            if !self.cols_to_features.contains_key(col_name.as_str()) {
-                self.cols_to_features
-                    .insert(col_name.clone(), HashMap::new());
-            }
-            let current_col_to_feats = self
-                .cols_to_features
-                .get_mut(col_name.as_str())
-                .expect("Unable to get col");
-
-            for (feature_name, feature_vec) in feature_res {
-                if !current_col_to_feats.contains_key(&feature_name) {
-                    current_col_to_feats.insert(feature_name.clone(), vec![]);
-                }
-                current_col_to_feats
-                    .get_mut(&feature_name)
-                    .unwrap()
-                    .append(&mut vec![feature_vec]);
-            }
-        }
+        let properties =
+            (self.get_vertex_properties_fn)(self, vertex_id.clone(), columns, field_names)?;
+
+        shard.push((vertex_id, properties));
+        Ok(())
+    }
+
+    fn merge_vertices(&mut self, shards: Vec<Vec<(String, Map<String, Value>)>>) -> Result<()> {
+        self.node_map.extend(shards.into_iter().flatten());
+        Ok(())
     }
 
     fn insert_edge(
-        &mut self,
+        &self,
+        shard: &mut Vec<ShardedEdge>,
         from_id: Vec<u8>,
         to_id: Vec<u8>,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
+        field_names: &[String],
     ) -> Result<()> {
-        debug_assert!(!columns.is_empty());
-        debug_assert_eq!(columns.len(), field_names.len());
-
-        let (from_col, from_key) = {
-            let s = String::from_utf8(from_id.clone()).expect("_from to be a string");
-            let id_split = s.find('/').expect("Invalid format for _from");
-            let (col, key) = s.split_at(id_split);
-            (col.to_string(), key[1..].to_string())
-        };
+        let from_id_str = parse_id(from_id, "_from")?;
+        let to_id_str = parse_id(to_id, "_to")?;
 
-        let (to_col, to_key) = {
-            let s = String::from_utf8(to_id.clone()).expect("_to to be a string");
-            let id_split = s.find('/').expect("Invalid format for _to");
-            let (col, key) = s.split_at(id_split);
-            (col.to_string(), key[1..].to_string())
-        };
+        let edge = (self.shard_edge_fn)(self, from_id_str, to_id_str, columns, field_names)?;
+        shard.push(edge);
 
-        // if either from_col or to_col is not part of the metagraph definition,
-        // we will not add it as an edge
-        if !self.cols_to_keys_to_inds.contains_key(&from_col) {
-            warn!(
-                "Skipping edge from {} to {} as {} is not part of the metagraph",
-                from_col, to_col, from_col
-            );
-            return Ok(());
-        }
-        if !self.cols_to_keys_to_inds.contains_key(&to_col) {
-            warn!(
-                "Skipping edge from {} to {} as {} is not part of the metagraph",
-                from_col, to_col, to_col
-            );
-            return Ok(());
+        Ok(())
+    }
+
+    fn merge_edges(&mut self, shards: Vec<Vec<ShardedEdge>>) -> Result<()> {
+        for edge in shards.into_iter().flatten() {
+            if let Some(properties) = edge.coo_properties {
+                (self.insert_coo_fn)(self, edge.from_id.clone(), edge.to_id.clone(), properties);
+            }
+            if let Some(properties) = edge.adj_properties {
+                (self.insert_adj_fn)(self, edge.from_id, edge.to_id, properties)?;
+            }
         }
+        Ok(())
+    }
 
-        debug_assert!(field_names.contains(&String::from("@collection_name")));
-        let col_name_position = field_names
-            .iter()
-            .position(|x| x == "@collection_name")
-            .expect("No @collection_name in edge field names");
-        let col_name = match &columns[col_name_position] {
-            Value::String(s) => s.as_str(),
-            _ => panic!("Expected Value::String for @collection_name"),
-        };
+    fn persist(&self) -> Option<&dyn Persist> {
+        Some(self)
+    }
 
-        let key_tup = (col_name.to_string(), from_col.clone(), to_col.clone());
-        if !self.coo_by_from_edge_to.contains_key(&key_tup) {
-            self.coo_by_from_edge_to
-                .insert(key_tup.clone(), vec![vec![], vec![]]);
-        }
-        let from_col_keys = self
-            .cols_to_keys_to_inds
-            .get(&from_col)
-            .ok_or_else(|| anyhow!("Unable to get keys `from` for {:?}", &from_col))?;
-        let to_col_keys = self
-            .cols_to_keys_to_inds
-            .get(&to_col)
-            .ok_or_else(|| anyhow!("Unable to get keys `to` for {:?}", &to_col))?;
-        let cur_coo = self
-            .coo_by_from_edge_to
-            .get_mut(&key_tup)
-            .ok_or_else(|| anyhow!("Unable to get COO from to for {:?}", &key_tup))?;
-        let from_col_id = from_col_keys.get(&from_key);
-        let to_col_id = to_col_keys.get(&to_key);
-        if let (Some(from_id), Some(to_id)) = (from_col_id, to_col_id) {
-            cur_coo[0].push(*from_id);
-            cur_coo[1].push(*to_id);
-        };
+    fn persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        Some(self)
+    }
+}
+
+impl Persist for NetworkXGraph {
+    fn describe(&self) -> Value {
+        self.description.clone()
+    }
+
+    // `edge_index_map` is left out, it is only needed while loading
+    fn save_data(&self, writer: &mut dyn Write) -> Result<()> {
+        let data = (
+            &self.node_map,
+            &self.adj_map_graph,
+            &self.adj_map_digraph,
+            &self.adj_map_multigraph,
+            &self.adj_map_multidigraph,
+            &self.coo,
+            &self.vertex_id_to_index,
+            &self.edge_indices,
+            &self.edge_values,
+        );
+        rmp_serde::encode::write(writer, &data)?;
+        Ok(())
+    }
+
+    fn restore_data(&mut self, reader: &mut dyn Read) -> Result<()> {
+        (
+            self.node_map,
+            self.adj_map_graph,
+            self.adj_map_digraph,
+            self.adj_map_multigraph,
+            self.adj_map_multidigraph,
+            self.coo,
+            self.vertex_id_to_index,
+            self.edge_indices,
+            self.edge_values,
+        ) = rmp_serde::decode::from_read(reader)?;
         Ok(())
     }
 }
 
-impl Graph for NetworkXGraph {
+// The attributes of a row, columns missing from it are null
+fn push_row(
+    columns: &mut BTreeMap<String, Vec<Value>>,
+    rows: usize,
+    attributes: Map<String, Value>,
+) {
+    for (name, value) in attributes {
+        columns
+            .entry(name)
+            .or_insert_with(|| vec![Value::Null; rows])
+            .push(value);
+    }
+    for column in columns.values_mut() {
+        column.resize(rows + 1, Value::Null);
+    }
+}
+
+// Attributes of a document, `system_attributes` are left out when all of
+// them were loaded
+fn row_attributes(
+    columns: Vec<Value>,
+    field_names: &[String],
+    system_attributes: &[&str],
+    document: &str,
+) -> Result<Map<String, Value>> {
+    if field_names.is_empty() {
+        return match columns.into_iter().next() {
+            Some(Value::Object(mut map)) => {
+                map.retain(|name, _| !system_attributes.contains(&name.as_str()));
+                Ok(map)
+            }
+            _ => {
+                let message = format!("Data of {} must be a json object", document);
+                Err(schema_error(None, None, None, message))
+            }
+        };
+    }
+    Ok(field_names
+        .iter()
+        .zip(columns)
+        .filter(|(name, _)| *name != "@collection_name" && *name != "_id")
+        .map(|(name, value)| (name.clone(), value))
+        .collect())
+}
+
+// The vertices of one collection, a row per vertex in index order
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ArrowVertexTable {
+    pub keys: Vec<String>,
+    pub keys_to_inds: HashMap<String, usize>,
+    pub columns: BTreeMap<String, Vec<Value>>,
+}
+
+// The edges of one (edge, from, to) triple, a row per edge
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ArrowEdgeTable {
+    pub from_indices: Vec<usize>,
+    pub to_indices: Vec<usize>,
+    pub columns: BTreeMap<String, Vec<Value>>,
+}
+
+// Requested attributes are kept as they are, typed columns are only built
+// once the tables are handed out
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ArrowGraph {
+    pub vertex_tables: HashMap<String, ArrowVertexTable>,
+    pub edge_tables: HashMap<(String, String, String), ArrowEdgeTable>,
+}
+
+// Vertices of one stream, per collection in insertion order
+pub type ArrowVertexShard = HashMap<String, Vec<(String, Map<String, Value>)>>;
+
+// Edges of one stream, already resolved to vertex indices
+pub type ArrowEdgeShard =
+    HashMap<(String, String, String), Vec<(usize, usize, Map<String, Value>)>>;
+
+impl ArrowGraph {
+    pub fn new() -> Arc<RwLock<ArrowGraph>> {
+        Arc::new(RwLock::new(ArrowGraph::default()))
+    }
+}
+
+impl Graph for ArrowGraph {
+    type VertexShard = ArrowVertexShard;
+    type EdgeShard = ArrowEdgeShard;
+
     fn insert_vertex(
-        &mut self,
+        &self,
+        shard: &mut ArrowVertexShard,
         id: Vec<u8>, // cannot be empty
         columns: Vec<Value>,
-        field_names: &Vec<String>,
-    ) {
-        let vertex_id = String::from_utf8(id.clone()).unwrap();
+        field_names: &[String],
+    ) -> Result<()> {
+        let vertex_id = parse_id(id, "_id")?;
+        let vertex = format!("vertex '{}'", vertex_id);
+        let (col_name, key) = match field_names.is_empty() {
+            // All attributes are loaded, there is no @collection_name then
+            true => split_id(&vertex_id, "_id", Some(&vertex_id), &vertex)?,
+            false => {
+                let col_name = collection_name(&columns, field_names, Some(&vertex_id), &vertex)?;
+                let key = vertex_id
+                    .split_once('/')
+                    .map_or_else(|| vertex_id.clone(), |(_, key)| key.to_string());
+                (col_name.to_string(), key)
+            }
+        };
+        let attributes = row_attributes(columns, field_names, &["_id", "_key", "_rev"], &vertex)?;
 
-        let properties =
-            (self.get_vertex_properties_fn)(self, vertex_id.clone(), columns, field_names);
+        shard.entry(col_name).or_default().push((key, attributes));
+        Ok(())
+    }
 
-        self.node_map.insert(vertex_id, properties.clone());
+    fn merge_vertices(&mut self, shards: Vec<ArrowVertexShard>) -> Result<()> {
+        for shard in shards {
+            for (col_name, vertices) in shard {
+                let table = self.vertex_tables.entry(col_name).or_default();
+                for (key, attributes) in vertices {
+                    let cur_ind = table.keys.len();
+                    push_row(&mut table.columns, cur_ind, attributes);
+                    table.keys_to_inds.insert(key.clone(), cur_ind);
+                    table.keys.push(key);
+                }
+            }
+        }
+        Ok(())
     }
 
     fn insert_edge(
-        &mut self,
+        &self,
+        shard: &mut ArrowEdgeShard,
         from_id: Vec<u8>,
         to_id: Vec<u8>,
         columns: Vec<Value>,
-        field_names: &Vec<String>,
+        field_names: &[String],
     ) -> Result<()> {
-        let from_id_str: String = String::from_utf8(from_id.clone()).unwrap();
-        let to_id_str: String = String::from_utf8(to_id.clone()).unwrap();
+        let from_id_str = parse_id(from_id, "_from")?;
+        let to_id_str = parse_id(to_id, "_to")?;
+        let edge_id = edge_document_id(&columns, field_names);
+        let edge = describe_edge(&from_id_str, &to_id_str);
+        let (from_col, from_key) = split_id(&from_id_str, "_from", edge_id, &edge)?;
+        let (to_col, to_key) = split_id(&to_id_str, "_to", edge_id, &edge)?;
+
+        // Edges between vertices which were not loaded are skipped, like
+        // the numpy graph does
+        let from_ind = self
+            .vertex_tables
+            .get(&from_col)
+            .and_then(|table| table.keys_to_inds.get(&from_key));
+        let to_ind = self
+            .vertex_tables
+            .get(&to_col)
+            .and_then(|table| table.keys_to_inds.get(&to_key));
+        let (Some(&from_ind), Some(&to_ind)) = (from_ind, to_ind) else {
+            return Ok(());
+        };
+
+        let col_name = match field_names.is_empty() {
+            // All attributes are loaded, there is no @collection_name then
+            true => match edge_id {
+                Some(id) => split_id(id, "_id", edge_id, &edge)?.0,
+                None => {
+                    let message = format!("No _id in the data of {}", edge);
+                    return Err(schema_error(None, None, Some("_id"), message));
+                }
+            },
+            false => collection_name(&columns, field_names, edge_id, &edge)?.to_string(),
+        };
+        let attributes = row_attributes(
+            columns,
+            field_names,
+            &["_id", "_key", "_rev", "_from", "_to"],
+            &edge,
+        )?;
+
+        shard
+            .entry((col_name, from_col, to_col))
+            .or_default()
+            .push((from_ind, to_ind, attributes));
+        Ok(())
+    }
 
-        (self.insert_edge_fn)(self, from_id_str, to_id_str, columns, field_names)?;
+    fn merge_edges(&mut self, shards: Vec<ArrowEdgeShard>) -> Result<()> {
+        for shard in shards {
+            for (key_tup, edges) in shard {
+                let table = self.edge_tables.entry(key_tup).or_default();
+                for (from_ind, to_ind, attributes) in edges {
+                    push_row(&mut table.columns, table.from_indices.len(), attributes);
+                    table.from_indices.push(from_ind);
+                    table.to_indices.push(to_ind);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn persist(&self) -> Option<&dyn Persist> {
+        Some(self)
+    }
+
+    fn persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        Some(self)
+    }
+}
+
+impl Persist for ArrowGraph {
+    fn describe(&self) -> Value {
+        json!({"graph": "arrow"})
+    }
+
+    fn save_data(&self, writer: &mut dyn Write) -> Result<()> {
+        rmp_serde::encode::write(writer, self)?;
+        Ok(())
+    }
 
+    fn restore_data(&mut self, reader: &mut dyn Read) -> Result<()> {
+        *self = rmp_serde::decode::from_read(reader)?;
         Ok(())
     }
 }
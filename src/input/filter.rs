@@ -0,0 +1,574 @@
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+
+// A small boolean expression language for per-collection filters, e.g.
+//   doc.country == "DE" && (doc.weight > 0.5 || !doc.deleted)
+// Attributes are addressed from `doc`, either as `doc.a.b` or `doc["a"]["b"]`.
+// Missing attributes evaluate to null.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterExpression {
+    Or(Box<FilterExpression>, Box<FilterExpression>),
+    And(Box<FilterExpression>, Box<FilterExpression>),
+    Not(Box<FilterExpression>),
+    Compare(Operand, CompareOp, Operand),
+    Truthy(Operand),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    Literal(Value),
+    Attribute(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl FilterExpression {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expression = parser.parse_or()?;
+        match parser.peek() {
+            Token::End => Ok(expression),
+            token => Err(format!(
+                "Unexpected {} at position {} in filter expression: {}",
+                token,
+                parser.offset(),
+                input
+            )),
+        }
+    }
+
+    // Top-level document attributes the expression reads. These have to be
+    // fetched alongside the requested fields so the filter can be evaluated.
+    pub fn attributes(&self) -> Vec<String> {
+        let mut attributes = vec![];
+        self.collect_attributes(&mut attributes);
+        attributes
+    }
+
+    fn collect_attributes(&self, attributes: &mut Vec<String>) {
+        let mut add = |operand: &Operand| {
+            if let Operand::Attribute(path) = operand {
+                if !attributes.contains(&path[0]) {
+                    attributes.push(path[0].clone());
+                }
+            }
+        };
+        match self {
+            FilterExpression::Or(l, r) | FilterExpression::And(l, r) => {
+                l.collect_attributes(attributes);
+                r.collect_attributes(attributes);
+            }
+            FilterExpression::Not(e) => e.collect_attributes(attributes),
+            FilterExpression::Compare(l, _, r) => {
+                add(l);
+                add(r);
+            }
+            FilterExpression::Truthy(o) => add(o),
+        }
+    }
+
+    // `lookup` resolves a top-level attribute of the document.
+    pub fn matches<'a>(&'a self, lookup: &impl Fn(&str) -> Option<&'a Value>) -> bool {
+        match self {
+            FilterExpression::Or(l, r) => l.matches(lookup) || r.matches(lookup),
+            FilterExpression::And(l, r) => l.matches(lookup) && r.matches(lookup),
+            FilterExpression::Not(e) => !e.matches(lookup),
+            FilterExpression::Compare(l, op, r) => {
+                compare(l.resolve(lookup), *op, r.resolve(lookup))
+            }
+            FilterExpression::Truthy(o) => is_truthy(o.resolve(lookup)),
+        }
+    }
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, lookup: &impl Fn(&str) -> Option<&'a Value>) -> &'a Value {
+        match self {
+            Operand::Literal(value) => value,
+            Operand::Attribute(path) => {
+                let mut current = lookup(&path[0]);
+                for part in &path[1..] {
+                    current = current.and_then(|v| match v {
+                        Value::Object(map) => map.get(part),
+                        Value::Array(array) => {
+                            part.parse::<usize>().ok().and_then(|i| array.get(i))
+                        }
+                        _ => None,
+                    });
+                }
+                current.unwrap_or(&Value::Null)
+            }
+        }
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64().partial_cmp(&r.as_f64()),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        (Value::Bool(l), Value::Bool(r)) => Some(l.cmp(r)),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        _ => None,
+    };
+    match op {
+        CompareOp::Eq => ordering == Some(Ordering::Equal) || (ordering.is_none() && left == right),
+        CompareOp::Ne => !compare(left, CompareOp::Eq, right),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Value),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    Dot,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    End,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Literal(value) => write!(f, "{}", value),
+            Token::Op(op) => write!(f, "{:?}", op),
+            Token::And => write!(f, "'&&'"),
+            Token::Or => write!(f, "'||'"),
+            Token::Not => write!(f, "'!'"),
+            Token::Dot => write!(f, "'.'"),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::LBracket => write!(f, "'['"),
+            Token::RBracket => write!(f, "']'"),
+            Token::End => write!(f, "end of input"),
+        }
+    }
+}
+
+// Tokens are paired with the character offset they start at, for error messages.
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+        let mut tokens = Positioned {
+            tokens: &mut tokens,
+            start,
+        };
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Eq));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                tokens.push(Token::Op(match (c, or_equal) {
+                    ('<', false) => CompareOp::Lt,
+                    ('<', true) => CompareOp::Le,
+                    ('>', false) => CompareOp::Gt,
+                    _ => CompareOp::Ge,
+                }));
+                i += if or_equal { 2 } else { 1 };
+            }
+            '"' | '\'' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(format!(
+                                "Unterminated string at position {} in filter: {}",
+                                start, input
+                            ))
+                        }
+                        Some(&q) if q == c => break,
+                        Some('\\') => {
+                            let escaped = chars.get(i + 1).ok_or_else(|| {
+                                format!(
+                                    "Unterminated string at position {} in filter: {}",
+                                    start, input
+                                )
+                            })?;
+                            s.push(match escaped {
+                                'n' => '\n',
+                                't' => '\t',
+                                other => *other,
+                            });
+                            i += 2;
+                        }
+                        Some(&other) => {
+                            s.push(other);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Literal(Value::String(s)));
+                i += 1;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric()
+                        || chars[i] == '.'
+                        || ((chars[i] == '-' || chars[i] == '+')
+                            && matches!(chars[i - 1], 'e' | 'E')))
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Literal(parse_number(&text, start)?));
+            }
+            c if c.is_alphabetic() || c == '_' || c == '@' => {
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '@')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    "null" => Token::Literal(Value::Null),
+                    _ => Token::Ident(word),
+                });
+            }
+            _ => {
+                return Err(format!(
+                    "Unexpected character '{}' at position {} in filter expression: {}",
+                    c, start, input
+                ))
+            }
+        }
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+struct Positioned<'a> {
+    tokens: &'a mut Vec<(Token, usize)>,
+    start: usize,
+}
+
+impl Positioned<'_> {
+    fn push(&mut self, token: Token) {
+        self.tokens.push((token, self.start));
+    }
+}
+
+fn parse_number(text: &str, start: usize) -> Result<Value, String> {
+    if let Ok(i) = text.parse::<i64>() {
+        return Ok(Value::from(i));
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+        .ok_or_else(|| {
+            format!(
+                "Invalid number '{}' at position {} in filter expression",
+                text, start
+            )
+        })
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    // Offset of the next token
+    fn offset(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        let offset = self.offset();
+        match self.advance() {
+            token if token == expected => Ok(()),
+            token => Err(format!(
+                "Expected {} but found {} at position {} in filter expression",
+                expected, token, offset
+            )),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpression, String> {
+        let mut left = self.parse_and()?;
+        while *self.peek() == Token::Or {
+            self.advance();
+            let right = self.parse_and()?;
+            left = FilterExpression::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpression, String> {
+        let mut left = self.parse_unary()?;
+        while *self.peek() == Token::And {
+            self.advance();
+            let right = self.parse_unary()?;
+            left = FilterExpression::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpression, String> {
+        match self.peek() {
+            Token::Not => {
+                self.advance();
+                Ok(FilterExpression::Not(Box::new(self.parse_unary()?)))
+            }
+            Token::LParen => {
+                self.advance();
+                let expression = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expression)
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<FilterExpression, String> {
+        let left = self.parse_operand()?;
+        if let Token::Op(op) = *self.peek() {
+            self.advance();
+            let right = self.parse_operand()?;
+            return Ok(FilterExpression::Compare(left, op, right));
+        }
+        Ok(FilterExpression::Truthy(left))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        let offset = self.offset();
+        match self.advance() {
+            Token::Literal(value) => Ok(Operand::Literal(value)),
+            Token::Ident(name) if name == "doc" => {
+                let mut path = vec![];
+                loop {
+                    match self.peek() {
+                        Token::Dot => {
+                            self.advance();
+                            let offset = self.offset();
+                            match self.advance() {
+                                Token::Ident(part) => path.push(part),
+                                token => {
+                                    return Err(format!(
+                                        "Expected attribute name after '.' but found {} at position {}",
+                                        token, offset
+                                    ))
+                                }
+                            }
+                        }
+                        Token::LBracket => {
+                            self.advance();
+                            let offset = self.offset();
+                            match self.advance() {
+                                Token::Literal(Value::String(part)) => path.push(part),
+                                Token::Literal(Value::Number(n)) if n.is_u64() => {
+                                    path.push(n.to_string())
+                                }
+                                token => {
+                                    return Err(format!(
+                                        "Expected string or index inside [] but found {} at position {}",
+                                        token, offset
+                                    ))
+                                }
+                            }
+                            self.expect(Token::RBracket)?;
+                        }
+                        _ => break,
+                    }
+                }
+                if path.is_empty() {
+                    return Err(
+                        "Filter expressions must compare attributes of doc, e.g. doc.name"
+                            .to_string(),
+                    );
+                }
+                Ok(Operand::Attribute(path))
+            }
+            token => Err(format!(
+                "Expected a literal or doc attribute but found {} at position {} in filter expression",
+                token, offset
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval(filter: &str, doc: Value) -> bool {
+        let expression = FilterExpression::parse(filter).unwrap();
+        expression.matches(&|name| doc.get(name))
+    }
+
+    fn attribute(path: &[&str]) -> Operand {
+        Operand::Attribute(path.iter().map(|p| p.to_string()).collect())
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let parsed = FilterExpression::parse("doc.a || doc.b && doc.c").unwrap();
+        assert_eq!(
+            parsed,
+            FilterExpression::Or(
+                Box::new(FilterExpression::Truthy(attribute(&["a"]))),
+                Box::new(FilterExpression::And(
+                    Box::new(FilterExpression::Truthy(attribute(&["b"]))),
+                    Box::new(FilterExpression::Truthy(attribute(&["c"]))),
+                )),
+            )
+        );
+        assert!(eval(
+            "doc.a || doc.b && doc.c",
+            json!({"a": true, "b": false})
+        ));
+        assert!(!eval(
+            "(doc.a || doc.b) && doc.c",
+            json!({"a": true, "b": false})
+        ));
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert!(eval("!doc.a && doc.b", json!({"a": false, "b": true})));
+        assert!(!eval("!(doc.a && doc.b)", json!({"a": true, "b": true})));
+        assert!(eval("!!doc.a", json!({"a": 1})));
+    }
+
+    #[test]
+    fn comparisons() {
+        let doc = json!({"n": 2, "s": "b", "nested": {"list": [10, 20]}});
+        assert!(eval("doc.n > 1.5 && doc.n <= 2 && doc.n != 3", doc.clone()));
+        assert!(eval("doc.s >= \"a\" && doc.s < 'c'", doc.clone()));
+        assert!(eval("doc.nested.list[1] == 20", doc.clone()));
+        assert!(eval("doc[\"nested\"][\"list\"][0] == 10", doc.clone()));
+        assert!(eval("doc.missing == null", doc.clone()));
+        // Values of different types are unordered
+        assert!(!eval("doc.s > 1", doc.clone()));
+        assert!(eval("doc.n == -2e0 || doc.n == 2.0", doc));
+    }
+
+    #[test]
+    fn quoting_and_escapes() {
+        let doc = json!({"s": "say \"hi\"\n", "t": "it's"});
+        assert!(eval(r#"doc.s == "say \"hi\"\n""#, doc.clone()));
+        assert!(eval(r#"doc.s == 'say "hi"\n'"#, doc.clone()));
+        assert!(eval(r#"doc.t == 'it\'s'"#, doc.clone()));
+        assert!(eval(r#"doc.t == "it's""#, doc));
+        assert_eq!(
+            FilterExpression::parse(r#"doc["a b"] == "\\""#).unwrap(),
+            FilterExpression::Compare(
+                attribute(&["a b"]),
+                CompareOp::Eq,
+                Operand::Literal(json!("\\")),
+            )
+        );
+    }
+
+    #[test]
+    fn collects_top_level_attributes() {
+        let parsed = FilterExpression::parse("doc.a.b == doc.c || !doc.a[0]").unwrap();
+        assert_eq!(parsed.attributes(), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn error_positions() {
+        let error = |filter: &str| FilterExpression::parse(filter).unwrap_err();
+        assert!(error("doc.a = 1").contains("'=' at position 6"));
+        assert!(error("doc.a == 'x").contains("Unterminated string at position 9"));
+        assert!(error("doc.a == \"x\\").contains("Unterminated string at position 9"));
+        assert!(error("(doc.a == 1").contains("found end of input at position 11"));
+        assert!(error("doc.a == 1)").contains("')' at position 10"));
+        assert!(error("doc. == 1").contains("Eq at position 5"));
+        assert!(error("doc[true]").contains("true at position 4"));
+        assert!(error("doc.a > 1.2.3").contains("'1.2.3' at position 8"));
+        assert!(error("name == 1").contains("'name' at position 0"));
+        assert!(error("doc == 1").contains("must compare attributes of doc"));
+    }
+}
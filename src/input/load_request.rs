@@ -1,12 +1,58 @@
+use crate::input::filter::FilterExpression;
+use crate::load::progress::ProgressCallback;
+use crate::load::retry::RetryPolicy;
+use crate::load::timeout::ClientTimeouts;
 use arangors_graph_exporter::{CollectionInfo, DataLoadConfiguration, DatabaseConfiguration};
+use reqwest_middleware::ClientWithMiddleware;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::time::Duration;
 
 pub struct DataLoadRequest {
     pub vertex_collections: Vec<CollectionInfo>,
     pub edge_collections: Vec<CollectionInfo>,
+    // Filters by collection name, only matching documents are loaded
+    pub vertex_filters: HashMap<String, FilterExpression>,
+    pub edge_filters: HashMap<String, FilterExpression>,
     pub db_config: DatabaseConfiguration,
     pub load_config: DataLoadConfiguration,
+    pub retry_policy: RetryPolicy,
+    pub timeouts: ClientTimeouts,
+    // The whole load fails once it takes longer
+    pub load_deadline: Option<Duration>,
+    pub file_source: Option<FileSourceConfiguration>,
+    // A named graph whose collections are loaded. The listed collections
+    // then only override the fields and filters of some of them.
+    pub graph_name: Option<String>,
+    pub vertex_query: Option<AqlQuery>,
+    pub edge_query: Option<AqlQuery>,
+    pub progress_callback: Option<ProgressCallback>,
+    // Number of documents per collection between two progress reports
+    pub progress_interval: u64,
+    // Completed phases are kept here, so a failed load can be resumed
+    pub checkpoint_dir: Option<String>,
+    // Results are stored here and reused while the collections are unchanged
+    pub cache_dir: Option<String>,
+    // Reused by the sources that talk HTTP themselves, set by a `Session`
+    pub client: Option<ClientWithMiddleware>,
 }
 
+#[derive(Clone)]
+pub struct AqlQuery {
+    // Vertex rows must contain `_id`, edge rows `_from` and `_to`
+    pub query: String,
+    pub bind_vars: Map<String, Value>,
+    // Projected row attributes to return, ignored with load_all_*_attributes
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct FileSourceConfiguration {
+    // Directory holding arangodump output (`.data.json[.gz]`) or JSONL (`.jsonl[.gz]`) files
+    pub path: String,
+}
+
+#[derive(Clone)]
 pub struct NetworkXGraphConfig {
     pub load_adj_dict: bool,
     pub load_coo: bool,
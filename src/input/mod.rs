@@ -1,2 +1,3 @@
+pub mod filter;
 pub mod load_request;
 pub mod parse;
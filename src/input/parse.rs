@@ -1,12 +1,32 @@
-use crate::input::load_request::{DataLoadRequest, NetworkXGraphConfig};
+use crate::graph::{
+    DiGraphAdjMap, GraphAdjMap, MultiDiGraphAdjMap, MultiGraphAdjMap, NetworkXGraph, NumpyGraph,
+};
+use crate::input::filter::FilterExpression;
+use crate::input::load_request::{
+    AqlQuery, DataLoadRequest, FileSourceConfiguration, NetworkXGraphConfig,
+};
+use crate::load::progress::{ProgressCallback, DEFAULT_PROGRESS_INTERVAL};
+use crate::load::retry::RetryPolicy;
+use crate::load::timeout::ClientTimeouts;
+use crate::output::compressed::CompressedFormat;
 use arangors_graph_exporter::graph_loader::CollectionInfo;
 use arangors_graph_exporter::{DataLoadConfiguration, DatabaseConfiguration};
+use numpy::{PyReadonlyArray1, PyReadonlyArray2};
 use pyo3::exceptions::PyValueError;
-use pyo3::types::PyDict;
-use pyo3::{FromPyObject, PyAny, PyResult};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple};
+use pyo3::{FromPyObject, PyAny, PyObject, PyResult, Python};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Default)]
-pub struct LocalDataLoadConfiguration(pub DataLoadConfiguration);
+pub struct LocalDataLoadConfiguration(
+    pub DataLoadConfiguration,
+    pub RetryPolicy,
+    // The load deadline
+    pub Option<Duration>,
+);
 
 impl From<LocalDataLoadConfiguration> for DataLoadConfiguration {
     fn from(local: LocalDataLoadConfiguration) -> Self {
@@ -14,49 +34,152 @@ impl From<LocalDataLoadConfiguration> for DataLoadConfiguration {
     }
 }
 #[derive(Default)]
-pub struct LocalDatabaseConfiguration(pub DatabaseConfiguration);
+pub struct LocalDatabaseConfiguration(pub DatabaseConfiguration, pub ClientTimeouts);
 
 impl From<LocalDatabaseConfiguration> for DatabaseConfiguration {
     fn from(local: LocalDatabaseConfiguration) -> Self {
         local.0
     }
 }
-pub struct LocalCollectionInfo(pub CollectionInfo);
+pub struct LocalCollectionInfo(pub CollectionInfo, pub Option<FilterExpression>);
 
-pub fn create_collection_info_vec(
-    collection_info: Vec<LocalCollectionInfo>,
-) -> Vec<CollectionInfo> {
+pub fn create_collection_info_vec(collection_info: &[LocalCollectionInfo]) -> Vec<CollectionInfo> {
     collection_info.iter().map(|c| c.0.clone()).collect()
 }
 
+pub fn create_collection_filter_map(
+    collection_info: &[LocalCollectionInfo],
+) -> HashMap<String, FilterExpression> {
+    collection_info
+        .iter()
+        .filter_map(|c| c.1.clone().map(|f| (c.0.name.clone(), f)))
+        .collect()
+}
+
 impl FromPyObject<'_> for DataLoadRequest {
     fn extract(ob: &PyAny) -> PyResult<Self> {
         let input_dict: &PyDict = ob.downcast()?;
-        let db_config: LocalDatabaseConfiguration = input_dict
+        let LocalDatabaseConfiguration(db_config, timeouts) = input_dict
             .get_item("database_config")?
             .map_or(Ok(LocalDatabaseConfiguration::default()), |c| c.extract())?;
-        let load_config: LocalDataLoadConfiguration = input_dict
+        let LocalDataLoadConfiguration(load_config, retry_policy, load_deadline) = input_dict
             .get_item("load_config")?
             .map_or(Ok(LocalDataLoadConfiguration::default()), |c| c.extract())?;
+        let vertex_query: Option<AqlQuery> = input_dict
+            .get_item("vertex_query")?
+            .map_or_else(|| Ok(None), |c| c.extract())?;
+        let edge_query: Option<AqlQuery> = input_dict
+            .get_item("edge_query")?
+            .map_or_else(|| Ok(None), |c| c.extract())?;
+        let is_query_request = vertex_query.is_some() || edge_query.is_some();
+        let graph_name: Option<String> = input_dict
+            .get_item("graph_name")?
+            .map_or_else(|| Ok(None), |c| c.extract())?;
+        if is_query_request && graph_name.is_some() {
+            return Err(PyValueError::new_err(
+                "vertex_query/edge_query cannot be combined with graph_name",
+            ));
+        }
+        // With a named graph the collections only override fields and filters
+        let collections_optional = is_query_request || graph_name.is_some();
         let vertex_collections: Vec<LocalCollectionInfo> =
             input_dict.get_item("vertex_collections")?.map_or_else(
-                || Err(PyValueError::new_err("vertex_collections not provided")),
+                || match collections_optional {
+                    true => Ok(vec![]),
+                    false => Err(PyValueError::new_err("vertex_collections not provided")),
+                },
                 |s| s.extract(),
             )?;
         let edge_collections: Vec<LocalCollectionInfo> =
             input_dict.get_item("edge_collections")?.map_or_else(
-                || Err(PyValueError::new_err("edge_collections not provided")),
+                || match collections_optional {
+                    true => Ok(vec![]),
+                    false => Err(PyValueError::new_err("edge_collections not provided")),
+                },
                 |s| s.extract(),
             )?;
+        if is_query_request && !(vertex_collections.is_empty() && edge_collections.is_empty()) {
+            return Err(PyValueError::new_err(
+                "vertex_query/edge_query cannot be combined with vertex_collections/edge_collections",
+            ));
+        }
+        let file_source: Option<FileSourceConfiguration> = input_dict
+            .get_item("file_source")?
+            .map_or_else(|| Ok(None), |c| c.extract())?;
+        if is_query_request && file_source.is_some() {
+            return Err(PyValueError::new_err(
+                "vertex_query/edge_query cannot be combined with file_source",
+            ));
+        }
+        if graph_name.is_some() && file_source.is_some() {
+            return Err(PyValueError::new_err(
+                "graph_name cannot be combined with file_source",
+            ));
+        }
+        let progress_callback: Option<ProgressCallback> = input_dict
+            .get_item("progress_callback")?
+            .filter(|c| !c.is_none())
+            .map(create_progress_callback)
+            .transpose()?;
+        let progress_interval: u64 = input_dict
+            .get_item("progress_interval")?
+            .map_or(Ok(DEFAULT_PROGRESS_INTERVAL), |v| v.extract())?;
+        if progress_interval == 0 {
+            return Err(PyValueError::new_err("progress_interval must be positive"));
+        }
+        let checkpoint_dir: Option<String> = input_dict
+            .get_item("checkpoint_dir")?
+            .map_or_else(|| Ok(None), |c| c.extract())?;
+        let cache_dir: Option<String> = input_dict
+            .get_item("cache_dir")?
+            .map_or_else(|| Ok(None), |c| c.extract())?;
+        // Only collections have revisions telling whether a result is current
+        if cache_dir.is_some() && (is_query_request || file_source.is_some()) {
+            return Err(PyValueError::new_err(
+                "cache_dir cannot be combined with vertex_query/edge_query or file_source",
+            ));
+        }
         Ok(DataLoadRequest {
-            vertex_collections: create_collection_info_vec(vertex_collections),
-            edge_collections: create_collection_info_vec(edge_collections),
-            load_config: load_config.into(),
-            db_config: db_config.into(),
+            vertex_collections: create_collection_info_vec(&vertex_collections),
+            edge_collections: create_collection_info_vec(&edge_collections),
+            vertex_filters: create_collection_filter_map(&vertex_collections),
+            edge_filters: create_collection_filter_map(&edge_collections),
+            load_config,
+            retry_policy,
+            timeouts,
+            load_deadline,
+            db_config,
+            file_source,
+            graph_name,
+            vertex_query,
+            edge_query,
+            progress_callback,
+            progress_interval,
+            checkpoint_dir,
+            cache_dir,
+            client: None,
         })
     }
 }
 
+// Wraps a Python callable taking (phase, collection, processed, elapsed)
+fn create_progress_callback(ob: &PyAny) -> PyResult<ProgressCallback> {
+    if !ob.is_callable() {
+        return Err(PyValueError::new_err("progress_callback must be callable"));
+    }
+    let callback: PyObject = ob.into();
+    Ok(Arc::new(
+        move |phase, collection_name, processed, elapsed| {
+            Python::with_gil(|py| {
+                callback
+                    .call1(py, (phase, collection_name, processed, elapsed))
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            })
+        },
+    ))
+}
+
 impl FromPyObject<'_> for LocalDataLoadConfiguration {
     fn extract(ob: &PyAny) -> PyResult<Self> {
         let input_dict: &PyDict = ob.downcast()?;
@@ -75,16 +198,77 @@ impl FromPyObject<'_> for LocalDataLoadConfiguration {
         let load_all_edge_attributes: bool = input_dict
             .get_item("load_all_edge_attributes")?
             .map_or(Ok(false), |v| v.extract())?;
-        Ok(LocalDataLoadConfiguration(DataLoadConfiguration {
-            parallelism,
-            batch_size,
-            prefetch_count,
-            load_all_vertex_attributes,
-            load_all_edge_attributes,
-        }))
+        let retry_policy: RetryPolicy = input_dict
+            .get_item("retry")?
+            .map_or(Ok(RetryPolicy::default()), |v| v.extract())?;
+        // In seconds, like all durations
+        let load_deadline: Option<Duration> = input_dict
+            .get_item("load_deadline")?
+            .map(|v| extract_timeout(v, "load_deadline"))
+            .transpose()?;
+        Ok(LocalDataLoadConfiguration(
+            DataLoadConfiguration {
+                parallelism,
+                batch_size,
+                prefetch_count,
+                load_all_vertex_attributes,
+                load_all_edge_attributes,
+            },
+            retry_policy,
+            load_deadline,
+        ))
     }
 }
 
+impl FromPyObject<'_> for RetryPolicy {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        let input_dict: &PyDict = ob.downcast()?;
+        let default = RetryPolicy::default();
+        let max_attempts: u32 = input_dict
+            .get_item("max_attempts")?
+            .map_or(Ok(default.max_attempts), |v| v.extract())?;
+        if max_attempts == 0 {
+            return Err(PyValueError::new_err("max_attempts must be positive"));
+        }
+        // Backoffs are given in seconds
+        let initial_backoff: Duration = input_dict
+            .get_item("initial_backoff")?
+            .map_or(Ok(default.initial_backoff), extract_duration)?;
+        let max_backoff: Duration = input_dict
+            .get_item("max_backoff")?
+            .map_or(Ok(default.max_backoff), extract_duration)?;
+        if initial_backoff > max_backoff {
+            return Err(PyValueError::new_err(
+                "initial_backoff must not exceed max_backoff",
+            ));
+        }
+        let retryable_status_codes: Vec<u16> = input_dict
+            .get_item("retryable_status_codes")?
+            .map_or(Ok(default.retryable_status_codes), |v| v.extract())?;
+        Ok(RetryPolicy {
+            max_attempts,
+            initial_backoff,
+            max_backoff,
+            retryable_status_codes,
+        })
+    }
+}
+
+fn extract_duration(ob: &PyAny) -> PyResult<Duration> {
+    let seconds: f64 = ob.extract()?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|e| PyValueError::new_err(format!("Invalid duration {}: {}", seconds, e)))
+}
+
+// A zero timeout would fail everything it applies to
+fn extract_timeout(ob: &PyAny, name: &str) -> PyResult<Duration> {
+    let timeout = extract_duration(ob)?;
+    if timeout.is_zero() {
+        return Err(PyValueError::new_err(format!("{} must be positive", name)));
+    }
+    Ok(timeout)
+}
+
 impl FromPyObject<'_> for LocalDatabaseConfiguration {
     fn extract(ob: &'_ PyAny) -> PyResult<Self> {
         let input_dict: &PyDict = ob.downcast()?;
@@ -106,14 +290,25 @@ impl FromPyObject<'_> for LocalDatabaseConfiguration {
         let tls_cert: Option<String> = input_dict
             .get_item("tls_cert")?
             .map_or_else(|| Ok(None), |c| c.extract())?;
-        Ok(LocalDatabaseConfiguration(DatabaseConfiguration {
-            database,
-            endpoints,
-            username,
-            password,
-            jwt_token,
-            tls_cert,
-        }))
+        let connect: Option<Duration> = input_dict
+            .get_item("connect_timeout")?
+            .map(|v| extract_timeout(v, "connect_timeout"))
+            .transpose()?;
+        let request: Option<Duration> = input_dict
+            .get_item("request_timeout")?
+            .map(|v| extract_timeout(v, "request_timeout"))
+            .transpose()?;
+        Ok(LocalDatabaseConfiguration(
+            DatabaseConfiguration {
+                database,
+                endpoints,
+                username,
+                password,
+                jwt_token,
+                tls_cert,
+            },
+            ClientTimeouts { connect, request },
+        ))
     }
 }
 
@@ -127,10 +322,111 @@ impl FromPyObject<'_> for LocalCollectionInfo {
         let fields: Vec<&str> = input_dict
             .get_item("fields")?
             .map_or_else(|| Ok(vec![]), |s| s.extract())?;
-        Ok(LocalCollectionInfo(CollectionInfo {
-            name: name.into(),
-            fields: fields.iter().map(|s| String::from(*s)).collect(),
-        }))
+        let filter: Option<&str> = input_dict
+            .get_item("filter")?
+            .map_or_else(|| Ok(None), |s| s.extract())?;
+        let filter = filter
+            .map(FilterExpression::parse)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Invalid filter for {}: {}", name, e)))?;
+        Ok(LocalCollectionInfo(
+            CollectionInfo {
+                name: name.into(),
+                fields: fields.iter().map(|s| String::from(*s)).collect(),
+            },
+            filter,
+        ))
+    }
+}
+
+impl FromPyObject<'_> for FileSourceConfiguration {
+    fn extract(ob: &'_ PyAny) -> PyResult<Self> {
+        let input_dict: &PyDict = ob.downcast()?;
+        let path: String = input_dict.get_item("path")?.map_or_else(
+            || Err(PyValueError::new_err("file_source path not set")),
+            |s| s.extract(),
+        )?;
+        Ok(FileSourceConfiguration { path })
+    }
+}
+
+impl FromPyObject<'_> for AqlQuery {
+    fn extract(ob: &'_ PyAny) -> PyResult<Self> {
+        let input_dict: &PyDict = ob.downcast()?;
+        let query: String = input_dict.get_item("query")?.map_or_else(
+            || Err(PyValueError::new_err("AQL query not set")),
+            |s| s.extract(),
+        )?;
+        let bind_vars: Map<String, Value> = match input_dict.get_item("bind_vars")? {
+            Some(b) => match py_to_json(b)? {
+                Value::Object(map) => map,
+                _ => return Err(PyValueError::new_err("bind_vars must be a dictionary")),
+            },
+            None => Map::new(),
+        };
+        let fields: Vec<String> = input_dict
+            .get_item("fields")?
+            .map_or_else(|| Ok(vec![]), |s| s.extract())?;
+        Ok(AqlQuery {
+            query,
+            bind_vars,
+            fields,
+        })
+    }
+}
+
+// Converts plain Python data (as used for AQL bind variables) into JSON
+pub fn py_to_json(ob: &PyAny) -> PyResult<Value> {
+    if ob.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = ob.downcast::<PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if let Ok(i) = ob.downcast::<PyLong>() {
+        match i.extract::<i64>() {
+            Ok(v) => Ok(Value::from(v)),
+            Err(_) => Ok(Value::from(i.extract::<u64>()?)),
+        }
+    } else if let Ok(f) = ob.downcast::<PyFloat>() {
+        serde_json::Number::from_f64(f.value())
+            .map(Value::Number)
+            .ok_or_else(|| PyValueError::new_err("NaN and infinity are not valid JSON"))
+    } else if let Ok(s) = ob.downcast::<PyString>() {
+        Ok(Value::String(s.to_str()?.to_string()))
+    } else if let Ok(list) = ob.downcast::<PyList>() {
+        list.iter()
+            .map(py_to_json)
+            .collect::<PyResult<_>>()
+            .map(Value::Array)
+    } else if let Ok(tuple) = ob.downcast::<PyTuple>() {
+        tuple
+            .iter()
+            .map(py_to_json)
+            .collect::<PyResult<_>>()
+            .map(Value::Array)
+    } else if let Ok(dict) = ob.downcast::<PyDict>() {
+        let mut map = Map::new();
+        for (k, v) in dict.iter() {
+            map.insert(k.extract::<String>()?, py_to_json(v)?);
+        }
+        Ok(Value::Object(map))
+    } else {
+        Err(PyValueError::new_err(format!(
+            "Unsupported value for JSON conversion: {}",
+            ob
+        )))
+    }
+}
+
+impl FromPyObject<'_> for CompressedFormat {
+    fn extract(ob: &'_ PyAny) -> PyResult<Self> {
+        match ob.extract::<&str>()? {
+            "csr" => Ok(CompressedFormat::Csr),
+            "csc" => Ok(CompressedFormat::Csc),
+            format => Err(PyValueError::new_err(format!(
+                "Unknown compressed format '{}', expected 'csr' or 'csc'",
+                format
+            ))),
+        }
     }
 }
 
@@ -161,3 +457,184 @@ impl FromPyObject<'_> for NetworkXGraphConfig {
         })
     }
 }
+
+fn extract_feature_rows<K: Eq + std::hash::Hash>(
+    features: HashMap<K, HashMap<String, PyReadonlyArray2<f64>>>,
+) -> HashMap<K, HashMap<String, Vec<Vec<f64>>>> {
+    features
+        .into_iter()
+        .map(|(name, feature_map)| {
+            let feature_map = feature_map
+                .into_iter()
+                .map(|(feature_name, arr)| {
+                    let rows = arr.as_array().outer_iter().map(|r| r.to_vec()).collect();
+                    (feature_name, rows)
+                })
+                .collect();
+            (name, feature_map)
+        })
+        .collect()
+}
+
+// Compressed formats hold a tuple of arrays per format where edge features
+// hold an array per feature, without edge features they come fifth
+fn holds_compressed_formats(item: &PyAny) -> bool {
+    let Ok(by_edge_type) = item.downcast::<PyDict>() else {
+        return false;
+    };
+    by_edge_type.values().iter().any(|formats| {
+        formats.downcast::<PyDict>().is_ok_and(|formats| {
+            formats
+                .values()
+                .iter()
+                .any(|format| format.is_instance_of::<PyTuple>())
+        })
+    })
+}
+
+// The result of a numpy load, e.g. one to be saved as a snapshot. The fifth
+// element holds the edge features if it was loaded with them, other
+// extra elements such as compressed formats are ignored.
+impl<'a> FromPyObject<'a> for NumpyGraph {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        let result: &PyTuple = ob.downcast()?;
+        if result.len() < 4 {
+            return Err(PyValueError::new_err(
+                "A numpy result has at least four elements",
+            ));
+        }
+        let features: HashMap<String, HashMap<String, PyReadonlyArray2<f64>>> =
+            result.get_item(0)?.extract()?;
+        let coo: HashMap<(String, String, String), PyReadonlyArray2<usize>> =
+            result.get_item(1)?.extract()?;
+        let cols_to_keys_to_inds: HashMap<String, HashMap<String, usize>> =
+            result.get_item(2)?.extract()?;
+        let coo_features: HashMap<
+            (String, String, String),
+            HashMap<String, PyReadonlyArray2<f64>>,
+        > = match result.get_item(4) {
+            Ok(item) if !holds_compressed_formats(item) => item.extract()?,
+            _ => HashMap::new(),
+        };
+        let cols_to_inds_to_keys = cols_to_keys_to_inds
+            .iter()
+            .map(|(col_name, keys_to_inds)| {
+                let inds_to_keys = keys_to_inds
+                    .iter()
+                    .map(|(key, index)| (*index, key.clone()))
+                    .collect();
+                (col_name.clone(), inds_to_keys)
+            })
+            .collect();
+        let cols_to_features = extract_feature_rows(features);
+        let coo_by_from_edge_to = coo
+            .into_iter()
+            .map(|(edge_type, arr)| {
+                let rows = arr.as_array().outer_iter().map(|r| r.to_vec()).collect();
+                (edge_type, rows)
+            })
+            .collect();
+        Ok(NumpyGraph {
+            cols_to_keys_to_inds,
+            cols_to_inds_to_keys,
+            coo_by_from_edge_to,
+            cols_to_features,
+            coo_features_by_from_edge_to: extract_feature_rows(coo_features),
+        })
+    }
+}
+
+fn extract_properties(ob: &PyAny) -> PyResult<Map<String, Value>> {
+    match py_to_json(ob)? {
+        Value::Object(properties) => Ok(properties),
+        _ => Err(PyValueError::new_err("properties must be a dictionary")),
+    }
+}
+
+fn extract_adj_map(ob: &PyAny) -> PyResult<GraphAdjMap> {
+    let adj: HashMap<String, HashMap<String, &PyAny>> = ob.extract()?;
+    adj.into_iter()
+        .map(|(from_id, neighbors)| {
+            let neighbors = neighbors
+                .into_iter()
+                .map(|(to_id, properties)| Ok((to_id, extract_properties(properties)?)))
+                .collect::<PyResult<_>>()?;
+            Ok((from_id, neighbors))
+        })
+        .collect()
+}
+
+fn extract_multi_adj_map(ob: &PyAny) -> PyResult<MultiGraphAdjMap> {
+    let adj: HashMap<String, HashMap<String, HashMap<usize, &PyAny>>> = ob.extract()?;
+    adj.into_iter()
+        .map(|(from_id, neighbors)| {
+            let neighbors = neighbors
+                .into_iter()
+                .map(|(to_id, edges)| {
+                    let edges = edges
+                        .into_iter()
+                        .map(|(index, properties)| Ok((index, extract_properties(properties)?)))
+                        .collect::<PyResult<_>>()?;
+                    Ok((to_id, edges))
+                })
+                .collect::<PyResult<_>>()?;
+            Ok((from_id, neighbors))
+        })
+        .collect()
+}
+
+type NetworkXResult<'a> = (
+    &'a PyDict,                  // node_dict
+    &'a PyDict,                  // adj_dict
+    PyReadonlyArray1<'a, usize>, // src_indices
+    PyReadonlyArray1<'a, usize>, // dst_indices
+    PyReadonlyArray1<'a, usize>, // edge_indices
+    HashMap<String, usize>,      // vertex_id_to_index
+    HashMap<String, Vec<f64>>,   // edge_values
+);
+
+// Fills `graph` from the result of a networkx load, the graph must be
+// created with the configuration of that load
+pub fn fill_networkx_graph(
+    graph: &mut NetworkXGraph,
+    result: &PyAny,
+    graph_config: &NetworkXGraphConfig,
+) -> PyResult<()> {
+    let (
+        node_dict,
+        adj_dict,
+        src_indices,
+        dst_indices,
+        edge_indices,
+        vertex_id_to_index,
+        edge_values,
+    ): NetworkXResult = result.extract()?;
+    let node_dict: HashMap<String, &PyAny> = node_dict.extract()?;
+    graph.node_map = node_dict
+        .into_iter()
+        .map(|(id, properties)| Ok((id, extract_properties(properties)?)))
+        .collect::<PyResult<_>>()?;
+    match (graph_config.is_multigraph, graph_config.is_directed) {
+        (true, true) => {
+            let adj: HashMap<String, &PyAny> = adj_dict.extract()?;
+            graph.adj_map_multidigraph = adj
+                .into_iter()
+                .map(|(direction, adj)| Ok((direction, extract_multi_adj_map(adj)?)))
+                .collect::<PyResult<MultiDiGraphAdjMap>>()?;
+        }
+        (true, false) => graph.adj_map_multigraph = extract_multi_adj_map(adj_dict)?,
+        (false, true) => {
+            let adj: HashMap<String, &PyAny> = adj_dict.extract()?;
+            graph.adj_map_digraph = adj
+                .into_iter()
+                .map(|(direction, adj)| Ok((direction, extract_adj_map(adj)?)))
+                .collect::<PyResult<DiGraphAdjMap>>()?;
+        }
+        (false, false) => graph.adj_map_graph = extract_adj_map(adj_dict)?,
+    }
+    graph.coo = (src_indices.to_vec()?, dst_indices.to_vec()?);
+    graph.edge_indices = edge_indices.to_vec()?;
+    graph.vertex_id_to_index = vertex_id_to_index;
+    graph.edge_values = edge_values;
+    Ok(())
+}
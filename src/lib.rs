@@ -1,47 +1,312 @@
+mod error;
 mod graph;
 mod input;
 mod load;
 mod output;
+mod pyclasses;
+use error::{ErrorKind, LoadError};
 use log::info;
 
 use input::load_request::{DataLoadRequest, NetworkXGraphConfig};
+use input::parse::fill_networkx_graph;
+use load::cancel::Cancellation;
 use numpy::PyArray1;
-#[cfg(not(test))]
+use output::arrow::{edge_batch, vertex_batch};
+use output::compressed::{compress_all, compress_coo_edge_map, CompressedFormat};
 use output::construct;
 use output::convert::{convert_coo_edge_map, convert_nested_features_map};
-#[cfg(not(test))]
+use output::parquet::{read_numpy_dataset, ParquetDataset, ParquetGraph};
+use output::snapshot::{write_snapshot, SnapshotReader};
+use pyclasses::{CancellationToken, Session};
 use pyo3::create_exception;
-#[cfg(not(test))]
-use pyo3::exceptions::PyException;
-#[cfg(not(test))]
+use pyo3::exceptions::{PyException, PyValueError};
 use pyo3::prelude::*;
-#[cfg(not(test))]
-use pyo3::types::PyDict;
+use pyo3::types::{PyCapsule, PyDict, PyTuple};
 
-use graph::{NetworkXGraph, NumpyGraph};
+use arrow_array::RecordBatch;
+use graph::{ArrowGraph, Graph, NetworkXGraph, NumpyGraph};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::Duration;
+use tokio::runtime::Runtime;
 
-#[cfg(not(test))]
 type PygCompatible<'a> = (&'a PyDict, &'a PyDict, &'a PyDict, &'a PyDict);
 
-#[cfg(not(test))]
+type NetworkXCompatible<'a> = (
+    &'a PyDict,          // node_dict
+    &'a PyDict,          // adj_dict
+    &'a PyArray1<usize>, // src_indices
+    &'a PyArray1<usize>, // dst_indices
+    &'a PyArray1<usize>, // edge_indices
+    &'a PyDict,          // vertex_id_to_index
+    &'a PyDict,          // edge_values
+);
+
+// Vertex tables by collection and edge tables by (edge, from, to)
+type ArrowCompatible<'a> = (&'a PyDict, &'a PyDict);
+
 create_exception!(phenolrs, PhenolError, PyException);
+create_exception!(phenolrs, PhenolCancelledError, PhenolError);
+create_exception!(phenolrs, PhenolConnectionError, PhenolError);
+create_exception!(phenolrs, PhenolAuthenticationError, PhenolError);
+create_exception!(phenolrs, PhenolCollectionNotFoundError, PhenolError);
+create_exception!(phenolrs, PhenolSchemaError, PhenolError);
+create_exception!(phenolrs, PhenolDuplicateEdgeError, PhenolError);
+create_exception!(phenolrs, PhenolTimeoutError, PhenolError);
 
-/// Loads a graph (from the name and description, into a PyG friendly format
-/// Requires numpy as a runtime dependency
-#[cfg(not(test))]
-#[pyfunction]
-#[cfg(not(test))]
-fn graph_to_numpy_format(py: Python, request: DataLoadRequest) -> PyResult<PygCompatible> {
-    let _ = env_logger::try_init();
+/// A table of `graph_to_arrow_format`. It implements the Arrow PyCapsule
+/// interface, so e.g. `pyarrow.record_batch(table)`, `polars.DataFrame(table)`
+/// or DuckDB read it without copying the data.
+#[pyclass]
+struct ArrowTable(RecordBatch);
 
-    let graph_factory = NumpyGraph::new;
+#[pymethods]
+impl ArrowTable {
+    #[getter]
+    fn num_rows(&self) -> usize {
+        self.0.num_rows()
+    }
 
-    info!("Retrieving Numpy Graph...");
-    let start_time = std::time::Instant::now();
-    let graph =
-        load::retrieve::get_arangodb_graph(request, graph_factory).map_err(PhenolError::new_err)?;
-    info!("Retrieved. Took: {:?}", start_time.elapsed());
+    #[getter]
+    fn column_names(&self) -> Vec<String> {
+        let schema = self.0.schema();
+        schema.fields().iter().map(|f| f.name().clone()).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.num_rows()
+    }
+
+    fn __arrow_c_schema__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyCapsule> {
+        output::arrow::schema_capsule(py, &self.0)
+    }
+
+    // A requested schema other than the table's own is not supported, it
+    // may be ignored as per the interface
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<(&'py PyCapsule, &'py PyCapsule)> {
+        let _ = requested_schema;
+        output::arrow::array_capsules(py, &self.0)
+    }
+
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<&'py PyCapsule> {
+        let _ = requested_schema;
+        output::arrow::stream_capsule(py, &self.0)
+    }
+}
+
+// Runs the load on `runtime` without holding the GIL. It is cancelled via
+// the token or when a signal handler raises, e.g. on Ctrl-C.
+fn load_graph<G: Graph + Send + Sync + 'static>(
+    py: Python,
+    request: DataLoadRequest,
+    graph_factory: impl FnOnce() -> Arc<RwLock<G>> + Send,
+    runtime: &Runtime,
+    cancel_token: Option<PyRef<CancellationToken>>,
+) -> PyResult<G> {
+    // A Ctrl-C only cancels this load, not everything sharing the token
+    let cancellation = create_cancellation(cancel_token);
+    // The exception raised by the signal handler, e.g. a KeyboardInterrupt,
+    // is raised again once the load has stopped instead of the load's error
+    let signal_error: Mutex<Option<PyErr>> = Mutex::new(None);
+    let interrupted = || {
+        Python::with_gil(|py| match py.check_signals() {
+            Ok(()) => false,
+            Err(e) => {
+                *signal_error.lock().unwrap() = Some(e);
+                true
+            }
+        })
+    };
+    let graph = py.allow_threads(|| {
+        load::retrieve::get_arangodb_graph(
+            request,
+            graph_factory,
+            runtime,
+            cancellation.clone(),
+            interrupted,
+        )
+    });
+    match signal_error.into_inner().unwrap() {
+        Some(signal_error) => Err(signal_error),
+        None => graph.map_err(|e| load_error(py, e, &cancellation)),
+    }
+}
+
+// Builds the Python objects of a finished load, called with the GIL held
+type PythonResultBuilder = Box<dyn FnOnce(Python) -> PyResult<PyObject> + Send>;
+
+/// A load running in the background on a shared tokio runtime. `wait`
+/// blocks without holding the GIL until it is done, `phenolrs.aio` awaits
+/// it in an executor thread. Dropping the task cancels the load.
+#[pyclass]
+struct LoadTask {
+    cancellation: Cancellation,
+    done: Arc<(Mutex<bool>, Condvar)>,
+    result: Arc<Mutex<Option<Result<PythonResultBuilder, LoadError>>>>,
+}
+
+#[pymethods]
+impl LoadTask {
+    fn done(&self) -> bool {
+        *self.done.0.lock().unwrap()
+    }
 
+    fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Waits until the load is done or `timeout` seconds have passed,
+    /// returns whether it is done.
+    #[pyo3(signature = (timeout = None))]
+    fn wait(&self, py: Python, timeout: Option<f64>) -> bool {
+        py.allow_threads(|| {
+            let (lock, condvar) = &*self.done;
+            let done = lock.lock().unwrap();
+            match timeout {
+                Some(seconds) => {
+                    let timeout = Duration::from_secs_f64(seconds.max(0.0));
+                    *condvar
+                        .wait_timeout_while(done, timeout, |d| !*d)
+                        .unwrap()
+                        .0
+                }
+                None => *condvar.wait_while(done, |d| !*d).unwrap(),
+            }
+        })
+    }
+
+    /// Returns the loaded graph, can only be called once the task is done
+    fn result(&self, py: Python) -> PyResult<PyObject> {
+        let result = self.result.lock().unwrap().take();
+        match result {
+            Some(Ok(build)) => build(py),
+            Some(Err(e)) => Err(load_error(py, e, &self.cancellation)),
+            None if self.done() => Err(PhenolError::new_err("Result was already retrieved")),
+            None => Err(PhenolError::new_err("Load is still running")),
+        }
+    }
+}
+
+impl Drop for LoadTask {
+    fn drop(&mut self) {
+        self.cancellation.cancel();
+    }
+}
+
+fn spawn_load<G: Graph + Send + Sync + 'static>(
+    request: DataLoadRequest,
+    graph_factory: impl FnOnce() -> Arc<RwLock<G>> + Send + 'static,
+    cancel_token: Option<PyRef<CancellationToken>>,
+    to_python: impl FnOnce(Python, G) -> PyResult<PyObject> + Send + 'static,
+) -> LoadTask {
+    let cancellation = create_cancellation(cancel_token);
+    let task = LoadTask {
+        cancellation: cancellation.clone(),
+        done: Arc::new((Mutex::new(false), Condvar::new())),
+        result: Arc::new(Mutex::new(None)),
+    };
+
+    let done = task.done.clone();
+    let result = task.result.clone();
+    load::retrieve::shared_runtime().spawn(async move {
+        let start_time = std::time::Instant::now();
+        let graph =
+            load::retrieve::get_arangodb_graph_async(request, graph_factory, cancellation).await;
+        let builder = graph.map(|graph| -> PythonResultBuilder {
+            info!("Retrieved. Took: {:?}", start_time.elapsed());
+            Box::new(move |py: Python| to_python(py, graph))
+        });
+        *result.lock().unwrap() = Some(builder);
+        let (lock, condvar) = &*done;
+        *lock.lock().unwrap() = true;
+        condvar.notify_all();
+    });
+    task
+}
+
+fn create_cancellation(cancel_token: Option<PyRef<CancellationToken>>) -> Cancellation {
+    cancel_token.map_or_else(Cancellation::default, |t| t.0.child())
+}
+
+// A cancelled load may fail with any error while winding down, it is
+// reported as cancelled regardless
+fn load_error(py: Python, error: LoadError, cancellation: &Cancellation) -> PyErr {
+    match cancellation.is_cancelled() {
+        true => PhenolCancelledError::new_err(error.message),
+        false => python_error(py, error),
+    }
+}
+
+// Raises the `PhenolError` subclass of the kind, with its details set as
+// attributes of the exception
+fn python_error(py: Python, error: LoadError) -> PyErr {
+    let LoadError { kind, message } = error;
+    let (err, attributes) = match kind {
+        ErrorKind::Connection => (PhenolConnectionError::new_err(message), vec![]),
+        ErrorKind::Authentication => (PhenolAuthenticationError::new_err(message), vec![]),
+        ErrorKind::CollectionNotFound { collection } => (
+            PhenolCollectionNotFoundError::new_err(message),
+            vec![("collection", collection)],
+        ),
+        ErrorKind::Schema {
+            collection,
+            document_id,
+            attribute,
+        } => (
+            PhenolSchemaError::new_err(message),
+            vec![
+                ("collection", collection),
+                ("document_id", document_id),
+                ("attribute", attribute),
+            ],
+        ),
+        ErrorKind::DuplicateEdge { from_id, to_id } => (
+            PhenolDuplicateEdgeError::new_err(message),
+            vec![("from_id", Some(from_id)), ("to_id", Some(to_id))],
+        ),
+        ErrorKind::Cancelled => (PhenolCancelledError::new_err(message), vec![]),
+        ErrorKind::Timeout => (PhenolTimeoutError::new_err(message), vec![]),
+        ErrorKind::Internal | ErrorKind::Other => (PhenolError::new_err(message), vec![]),
+    };
+    for (name, value) in attributes {
+        if let Err(e) = err.value(py).setattr(name, value) {
+            return e;
+        }
+    }
+    err
+}
+
+fn networkx_graph_factory(
+    graph_config: &NetworkXGraphConfig,
+    request: &DataLoadRequest,
+) -> impl Fn() -> Arc<RwLock<NetworkXGraph>> + Send + 'static {
+    let graph_config = graph_config.clone();
+    let load_all_vertex_attributes = request.load_config.load_all_vertex_attributes;
+    let load_all_edge_attributes = request.load_config.load_all_edge_attributes;
+    move || {
+        NetworkXGraph::new(
+            graph_config.load_adj_dict,
+            graph_config.load_coo,
+            load_all_vertex_attributes,
+            load_all_edge_attributes,
+            graph_config.is_directed,
+            graph_config.is_multigraph,
+            graph_config.symmetrize_edges_if_directed,
+        )
+    }
+}
+
+fn numpy_graph_to_python(py: Python, graph: NumpyGraph) -> PyResult<PygCompatible> {
     info!("Building python objects...");
     let start_time = std::time::Instant::now();
     let col_to_features = construct::construct_col_to_features(
@@ -71,44 +336,129 @@ fn graph_to_numpy_format(py: Python, request: DataLoadRequest) -> PyResult<PygCo
     Ok(res)
 }
 
-#[pyfunction]
-#[cfg(not(test))]
-fn graph_to_networkx_format(
+// The result of graph_to_numpy_format, with the edge features and the
+// compressed formats appended if requested
+fn numpy_result_to_python(
     py: Python,
-    request: DataLoadRequest,
-    graph_config: NetworkXGraphConfig,
-) -> PyResult<(
-    &PyDict,          // node_dict
-    &PyDict,          // adj_dict
-    &PyArray1<usize>, // src_indices
-    &PyArray1<usize>, // dst_indices
-    &PyArray1<usize>, // edge_indices
-    &PyDict,          // vertex_id_to_index
-    &PyDict,          // edge_values
-)> {
-    let _ = env_logger::try_init();
-
-    let load_all_vertex_attributes = request.load_config.load_all_vertex_attributes;
-    let load_all_edge_attributes = request.load_config.load_all_edge_attributes;
+    mut graph: NumpyGraph,
+    edge_features: bool,
+    compressed_formats: Option<Vec<CompressedFormat>>,
+) -> PyResult<PyObject> {
+    if !edge_features && compressed_formats.is_none() {
+        return Ok(numpy_graph_to_python(py, graph)?.into_py(py));
+    }
+    let mut extra: Vec<PyObject> = vec![];
+    if edge_features {
+        let coo_features = std::mem::take(&mut graph.coo_features_by_from_edge_to);
+        let coo_features =
+            construct::construct_col_to_features(convert_nested_features_map(coo_features), py)?;
+        extra.push(coo_features.into_py(py));
+    }
+    if let Some(formats) = compressed_formats {
+        let compressed = py.allow_threads(|| {
+            compress_coo_edge_map(
+                &graph.coo_by_from_edge_to,
+                &graph.cols_to_inds_to_keys,
+                &formats,
+            )
+        });
+        let compressed = construct::construct_compressed_by_from_edge_to(compressed, py)?;
+        extra.push(compressed.into_py(py));
+    }
+    let (features, coo, keys_to_inds, inds_to_keys) = numpy_graph_to_python(py, graph)?;
+    let mut res: Vec<PyObject> = vec![
+        features.into_py(py),
+        coo.into_py(py),
+        keys_to_inds.into_py(py),
+        inds_to_keys.into_py(py),
+    ];
+    res.append(&mut extra);
+    Ok(PyTuple::new(py, res).into_py(py))
+}
 
-    let graph_factory = || {
-        NetworkXGraph::new(
-            graph_config.load_adj_dict,
-            graph_config.load_coo,
-            load_all_vertex_attributes,
-            load_all_edge_attributes,
-            graph_config.is_directed,
-            graph_config.is_multigraph,
-            graph_config.symmetrize_edges_if_directed,
-        )
+// The result of graph_to_networkx_format, with the compressed formats
+// appended if requested
+fn networkx_result_to_python(
+    py: Python,
+    graph: NetworkXGraph,
+    graph_config: &NetworkXGraphConfig,
+    compressed_formats: Option<Vec<CompressedFormat>>,
+) -> PyResult<PyObject> {
+    let formats = match compressed_formats {
+        Some(formats) => formats,
+        None => return Ok(networkx_graph_to_python(py, graph, graph_config)?.into_py(py)),
     };
+    // Indices are assigned to the vertices of the edges only, without gaps
+    let vertex_count = graph.vertex_id_to_index.len();
+    let compressed = py.allow_threads(|| {
+        let (from, to) = &graph.coo;
+        compress_all(from, to, vertex_count, vertex_count, &formats)
+    });
+    let compressed = construct::construct_compressed_adjacency(compressed, py)?;
+    let (node_dict, adj_dict, src, dst, edge_indices, id_to_index, edge_values) =
+        networkx_graph_to_python(py, graph, graph_config)?;
+    let res = (
+        node_dict,
+        adj_dict,
+        src,
+        dst,
+        edge_indices,
+        id_to_index,
+        edge_values,
+        compressed,
+    );
+    Ok(res.into_py(py))
+}
 
-    info!("Retrieving NetworkX Graph...");
+fn check_compressed_formats(
+    graph_config: &NetworkXGraphConfig,
+    compressed_formats: &Option<Vec<CompressedFormat>>,
+) -> PyResult<()> {
+    if compressed_formats.is_some() && !graph_config.load_coo {
+        return Err(PyValueError::new_err(
+            "compressed_formats requires load_coo",
+        ));
+    }
+    Ok(())
+}
+
+fn arrow_graph_to_python(py: Python, graph: ArrowGraph) -> PyResult<ArrowCompatible> {
+    info!("Building Arrow tables...");
     let start_time = std::time::Instant::now();
-    let graph =
-        load::retrieve::get_arangodb_graph(request, graph_factory).map_err(PhenolError::new_err)?;
-    info!("Retrieved. Took: {:?}", start_time.elapsed());
+    let (vertex_batches, edge_batches) = py
+        .allow_threads(|| -> Result<_, LoadError> {
+            let vertex_batches = graph
+                .vertex_tables
+                .iter()
+                .map(|(col_name, table)| Ok((col_name.clone(), vertex_batch(table)?)))
+                .collect::<Result<Vec<_>, LoadError>>()?;
+            let edge_batches = graph
+                .edge_tables
+                .iter()
+                .map(|(key_tup, table)| Ok((key_tup.clone(), edge_batch(table)?)))
+                .collect::<Result<Vec<_>, LoadError>>()?;
+            Ok((vertex_batches, edge_batches))
+        })
+        .map_err(|e| python_error(py, e))?;
+
+    let vertex_tables = PyDict::new(py);
+    for (col_name, batch) in vertex_batches {
+        vertex_tables.set_item(col_name, Py::new(py, ArrowTable(batch))?)?;
+    }
+    let edge_tables = PyDict::new(py);
+    for (key_tup, batch) in edge_batches {
+        edge_tables.set_item(key_tup, Py::new(py, ArrowTable(batch))?)?;
+    }
+    info!("Built. Took: {:?}", start_time.elapsed());
 
+    Ok((vertex_tables, edge_tables))
+}
+
+fn networkx_graph_to_python<'a>(
+    py: Python<'a>,
+    graph: NetworkXGraph,
+    graph_config: &NetworkXGraphConfig,
+) -> PyResult<NetworkXCompatible<'a>> {
     info!("Building python objects...");
     let start_time = std::time::Instant::now();
     let node_dict = construct::construct_node_dict(graph.node_map, py)?;
@@ -147,13 +497,340 @@ fn graph_to_networkx_format(
     Ok(res)
 }
 
+/// Loads a graph (from the name and description, into a PyG friendly format
+/// Requires numpy as a runtime dependency
+/// With `edge_features`, the next element holds the numeric attributes of
+/// the edges requested in `edge_collections`, one matrix per attribute and
+/// (edge, from, to) triple with a row per COO column, e.g.
+/// `{("knows", "person", "person"): {"weight": array([[1.0], [0.5]])}}`.
+/// Edges without a value get a row of NaN.
+/// With `compressed_formats`, e.g. `["csr", "csc"]`, the last element holds
+/// the edges of each (edge, from, to) triple in those formats as well, e.g.
+/// `{("knows", "person", "person"): {"csr": (indptr, indices, permutation)}}`.
+/// CSR rows are the `from` vertices, CSC columns the `to` vertices and the
+/// permutation maps each compressed edge to its position in the COO arrays.
+#[pyfunction]
+#[pyo3(signature = (request, cancel_token = None, compressed_formats = None, edge_features = false))]
+fn graph_to_numpy_format(
+    py: Python,
+    request: DataLoadRequest,
+    cancel_token: Option<PyRef<CancellationToken>>,
+    compressed_formats: Option<Vec<CompressedFormat>>,
+    edge_features: bool,
+) -> PyResult<PyObject> {
+    let _ = env_logger::try_init();
+
+    let graph_factory = NumpyGraph::new;
+
+    info!("Retrieving Numpy Graph...");
+    let start_time = std::time::Instant::now();
+    let graph = load_graph(
+        py,
+        request,
+        graph_factory,
+        load::retrieve::shared_runtime(),
+        cancel_token,
+    )?;
+    info!("Retrieved. Took: {:?}", start_time.elapsed());
+
+    numpy_result_to_python(py, graph, edge_features, compressed_formats)
+}
+
+/// Loads a graph into Arrow tables, one per vertex collection with the
+/// `_key` and `index` of each vertex and its requested attributes, and one
+/// per (edge, from, to) collection triple with the `from_index` and
+/// `to_index` of each edge into the vertex tables and its requested
+/// attributes. Edges between vertices which were not loaded are skipped.
+#[pyfunction]
+#[pyo3(signature = (request, cancel_token = None))]
+fn graph_to_arrow_format<'a>(
+    py: Python<'a>,
+    request: DataLoadRequest,
+    cancel_token: Option<PyRef<CancellationToken>>,
+) -> PyResult<ArrowCompatible<'a>> {
+    let _ = env_logger::try_init();
+
+    info!("Retrieving Arrow Graph...");
+    let start_time = std::time::Instant::now();
+    let graph = load_graph(
+        py,
+        request,
+        ArrowGraph::new,
+        load::retrieve::shared_runtime(),
+        cancel_token,
+    )?;
+    info!("Retrieved. Took: {:?}", start_time.elapsed());
+
+    arrow_graph_to_python(py, graph)
+}
+
+/// Starts `graph_to_numpy_format` in the background, returns a `LoadTask`
+#[pyfunction]
+#[pyo3(signature = (request, cancel_token = None, compressed_formats = None, edge_features = false))]
+fn start_graph_to_numpy_format(
+    request: DataLoadRequest,
+    cancel_token: Option<PyRef<CancellationToken>>,
+    compressed_formats: Option<Vec<CompressedFormat>>,
+    edge_features: bool,
+) -> LoadTask {
+    let _ = env_logger::try_init();
+
+    info!("Retrieving Numpy Graph in the background...");
+    spawn_load(request, NumpyGraph::new, cancel_token, move |py, graph| {
+        numpy_result_to_python(py, graph, edge_features, compressed_formats)
+    })
+}
+
+/// Loads a graph into the format `NetworkXLoader` builds the graphs from.
+/// With `compressed_formats`, e.g. `["csr", "csc"]`, an eighth element holds
+/// the COO edges in those formats as well, e.g. `{"csr": (indptr, indices,
+/// permutation)}`, see `graph_to_numpy_format`. Requires `load_coo`.
+#[pyfunction]
+#[pyo3(signature = (request, graph_config, cancel_token = None, compressed_formats = None))]
+fn graph_to_networkx_format(
+    py: Python,
+    request: DataLoadRequest,
+    graph_config: NetworkXGraphConfig,
+    cancel_token: Option<PyRef<CancellationToken>>,
+    compressed_formats: Option<Vec<CompressedFormat>>,
+) -> PyResult<PyObject> {
+    let _ = env_logger::try_init();
+
+    check_compressed_formats(&graph_config, &compressed_formats)?;
+    let graph_factory = networkx_graph_factory(&graph_config, &request);
+
+    info!("Retrieving NetworkX Graph...");
+    let start_time = std::time::Instant::now();
+    let graph = load_graph(
+        py,
+        request,
+        graph_factory,
+        load::retrieve::shared_runtime(),
+        cancel_token,
+    )?;
+    info!("Retrieved. Took: {:?}", start_time.elapsed());
+
+    networkx_result_to_python(py, graph, &graph_config, compressed_formats)
+}
+
+/// Starts `graph_to_networkx_format` in the background, returns a `LoadTask`
+#[pyfunction]
+#[pyo3(signature = (request, graph_config, cancel_token = None, compressed_formats = None))]
+fn start_graph_to_networkx_format(
+    request: DataLoadRequest,
+    graph_config: NetworkXGraphConfig,
+    cancel_token: Option<PyRef<CancellationToken>>,
+    compressed_formats: Option<Vec<CompressedFormat>>,
+) -> PyResult<LoadTask> {
+    let _ = env_logger::try_init();
+
+    check_compressed_formats(&graph_config, &compressed_formats)?;
+    let graph_factory = networkx_graph_factory(&graph_config, &request);
+
+    info!("Retrieving NetworkX Graph in the background...");
+    Ok(spawn_load(
+        request,
+        graph_factory,
+        cancel_token,
+        move |py, graph| networkx_result_to_python(py, graph, &graph_config, compressed_formats),
+    ))
+}
+
+/// Loads a graph like `graph_to_numpy_format`, but writes it to a Parquet
+/// dataset in `path` as it loads instead of returning it. The directory
+/// must be empty or missing. There is a dataset per vertex collection with
+/// the `_key`, `index` and features of each vertex, and one per (edge, from,
+/// to) collection triple with the COO indices and features of each edge,
+/// partitioned like Hive does, e.g.
+/// `vertices/collection=person/part-00000.parquet`. A `_SUCCESS` file is
+/// written once the export is complete, a failed one leaves the directory
+/// without it. See `load_numpy_parquet` to read it back.
+#[pyfunction]
+#[pyo3(signature = (request, path, cancel_token = None))]
+fn graph_to_parquet(
+    py: Python,
+    request: DataLoadRequest,
+    path: PathBuf,
+    cancel_token: Option<PyRef<CancellationToken>>,
+) -> PyResult<()> {
+    let _ = env_logger::try_init();
+
+    if request.cache_dir.is_some() {
+        return Err(PyValueError::new_err(
+            "Parquet exports cannot be combined with cache_dir",
+        ));
+    }
+    let dataset = ParquetDataset::create(&path).map_err(|e| python_error(py, e))?;
+
+    info!("Exporting Graph to Parquet...");
+    let start_time = std::time::Instant::now();
+    load_graph(
+        py,
+        request,
+        move || ParquetGraph::new(dataset),
+        load::retrieve::shared_runtime(),
+        cancel_token,
+    )?;
+    info!("Exported. Took: {:?}", start_time.elapsed());
+    Ok(())
+}
+
+/// Reads a dataset written by `graph_to_parquet` into the result
+/// `graph_to_numpy_format` returns for the same request, with
+/// `edge_features` including the edge features as the fifth element.
+#[pyfunction]
+#[pyo3(signature = (path, edge_features = false))]
+fn load_numpy_parquet(py: Python, path: PathBuf, edge_features: bool) -> PyResult<PyObject> {
+    let graph = py
+        .allow_threads(|| read_numpy_dataset(&path))
+        .map_err(|e| python_error(py, e))?;
+    numpy_result_to_python(py, graph, edge_features, None)
+}
+
+/// Saves the result of `graph_to_numpy_format` to `path`, including the edge
+/// features if it was loaded with `edge_features`. The snapshot can be
+/// loaded again with `load_numpy_snapshot`, without a database.
+#[pyfunction]
+fn save_numpy_snapshot(py: Python, path: PathBuf, result: NumpyGraph) -> PyResult<()> {
+    py.allow_threads(|| write_snapshot(&path, &result))
+        .map_err(|e| python_error(py, e))
+}
+
+/// With `edge_features`, the fifth element holds the edge features saved
+/// with the snapshot, empty if it has none.
+#[pyfunction]
+#[pyo3(signature = (path, edge_features = false))]
+fn load_numpy_snapshot(py: Python, path: PathBuf, edge_features: bool) -> PyResult<PyObject> {
+    let graph = py
+        .allow_threads(|| {
+            let graph = NumpyGraph::new();
+            SnapshotReader::open(&path)?.restore(&mut *graph.write().unwrap())?;
+            load::retrieve::unwrap_graph(graph)
+        })
+        .map_err(|e| python_error(py, e))?;
+    numpy_result_to_python(py, graph, edge_features, None)
+}
+
+/// Saves the result of `graph_to_networkx_format` to `path`, `graph_config`
+/// must be the one the result was loaded with. The snapshot can be loaded
+/// again with `load_networkx_snapshot`, without a database.
+#[pyfunction]
+fn save_networkx_snapshot(
+    py: Python,
+    path: PathBuf,
+    result: &PyAny,
+    graph_config: NetworkXGraphConfig,
+) -> PyResult<()> {
+    let graph = networkx_snapshot_graph(&graph_config);
+    let mut graph = graph.write().unwrap();
+    fill_networkx_graph(&mut graph, result, &graph_config)?;
+    py.allow_threads(|| write_snapshot(&path, &*graph))
+        .map_err(|e| python_error(py, e))
+}
+
+#[pyfunction]
+fn load_networkx_snapshot(py: Python<'_>, path: PathBuf) -> PyResult<NetworkXCompatible<'_>> {
+    let (graph, graph_config) = py
+        .allow_threads(|| {
+            let reader = SnapshotReader::open(&path)?;
+            let option = |name: &str| reader.description()[name].as_bool().unwrap_or_default();
+            let graph_config = NetworkXGraphConfig {
+                load_adj_dict: option("load_adj_dict"),
+                load_coo: option("load_coo"),
+                is_directed: option("is_directed"),
+                is_multigraph: option("is_multigraph"),
+                symmetrize_edges_if_directed: option("symmetrize_edges_if_directed"),
+            };
+            let graph = networkx_snapshot_graph(&graph_config);
+            reader.restore(&mut *graph.write().unwrap())?;
+            Ok((load::retrieve::unwrap_graph(graph)?, graph_config))
+        })
+        .map_err(|e| python_error(py, e))?;
+    networkx_graph_to_python(py, graph, &graph_config)
+}
+
+// Which attributes were loaded does not matter for restoring a result
+fn networkx_snapshot_graph(graph_config: &NetworkXGraphConfig) -> Arc<RwLock<NetworkXGraph>> {
+    NetworkXGraph::new(
+        graph_config.load_adj_dict,
+        graph_config.load_coo,
+        false,
+        false,
+        graph_config.is_directed,
+        graph_config.is_multigraph,
+        graph_config.symmetrize_edges_if_directed,
+    )
+}
+
+/// Reports document counts, a sample-based schema of the requested fields and
+/// the (edge, from, to) collection triples of the requested collections,
+/// without loading the graph. Takes the same request as the loads.
+#[pyfunction]
+#[pyo3(signature = (request, sample_size = 1000))]
+fn inspect_graph(py: Python<'_>, request: DataLoadRequest, sample_size: u64) -> PyResult<&PyDict> {
+    let _ = env_logger::try_init();
+
+    if request.vertex_query.is_some()
+        || request.edge_query.is_some()
+        || request.file_source.is_some()
+    {
+        return Err(PyValueError::new_err(
+            "inspect_graph only inspects collections of a database",
+        ));
+    }
+
+    info!("Inspecting graph...");
+    let inspection = py
+        .allow_threads(|| {
+            load::retrieve::shared_runtime()
+                .block_on(load::inspect::inspect_graph(request, sample_size))
+        })
+        .map_err(|e| python_error(py, e))?;
+
+    construct::construct_inspection(inspection, py)
+}
+
 /// A Python module implemented in Rust.
-#[cfg(not(test))]
 #[pymodule]
-#[cfg(not(test))]
 fn phenolrs(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(graph_to_numpy_format, m)?)?;
     m.add_function(wrap_pyfunction!(graph_to_networkx_format, m)?)?;
+    m.add_function(wrap_pyfunction!(graph_to_arrow_format, m)?)?;
+    m.add_function(wrap_pyfunction!(start_graph_to_numpy_format, m)?)?;
+    m.add_function(wrap_pyfunction!(start_graph_to_networkx_format, m)?)?;
+    m.add_function(wrap_pyfunction!(inspect_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(save_numpy_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(load_numpy_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(save_networkx_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(load_networkx_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(graph_to_parquet, m)?)?;
+    m.add_function(wrap_pyfunction!(load_numpy_parquet, m)?)?;
+    m.add_class::<CancellationToken>()?;
+    m.add_class::<LoadTask>()?;
+    m.add_class::<Session>()?;
+    m.add_class::<ArrowTable>()?;
     m.add("PhenolError", py.get_type::<PhenolError>())?;
+    m.add(
+        "PhenolCancelledError",
+        py.get_type::<PhenolCancelledError>(),
+    )?;
+    m.add(
+        "PhenolConnectionError",
+        py.get_type::<PhenolConnectionError>(),
+    )?;
+    m.add(
+        "PhenolAuthenticationError",
+        py.get_type::<PhenolAuthenticationError>(),
+    )?;
+    m.add(
+        "PhenolCollectionNotFoundError",
+        py.get_type::<PhenolCollectionNotFoundError>(),
+    )?;
+    m.add("PhenolSchemaError", py.get_type::<PhenolSchemaError>())?;
+    m.add(
+        "PhenolDuplicateEdgeError",
+        py.get_type::<PhenolDuplicateEdgeError>(),
+    )?;
+    m.add("PhenolTimeoutError", py.get_type::<PhenolTimeoutError>())?;
     Ok(())
 }
@@ -0,0 +1,271 @@
+use crate::error::LoadError;
+use crate::input::load_request::AqlQuery;
+use crate::load::document::{collection_name_from_id, extract_columns, get_string_attribute};
+use crate::load::retry::{build_client, RetryPolicy};
+use crate::load::source::{BatchId, EdgeBatchHandler, GraphSource, VertexBatchHandler};
+use crate::load::timeout::ClientTimeouts;
+use arangors_graph_exporter::client::auth::handle_auth;
+use arangors_graph_exporter::client::make_url;
+use arangors_graph_exporter::request::handle_arangodb_response_with_parsed_body;
+use arangors_graph_exporter::{DataLoadConfiguration, DatabaseConfiguration};
+use log::{debug, info};
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CursorResult {
+    result: Vec<Value>,
+    #[serde(default)]
+    has_more: bool,
+    id: Option<String>,
+    // Only sent by servers supporting retries of cursor batches
+    next_batch_id: Option<Value>,
+}
+
+// Loads vertices and edges from user supplied AQL queries instead of whole
+// collections. A missing query means that part of the graph is not loaded.
+pub struct AqlSource {
+    db_config: DatabaseConfiguration,
+    load_config: DataLoadConfiguration,
+    vertex_query: Option<AqlQuery>,
+    edge_query: Option<AqlQuery>,
+    client: ClientWithMiddleware,
+}
+
+impl AqlSource {
+    pub fn new(
+        db_config: DatabaseConfiguration,
+        load_config: DataLoadConfiguration,
+        vertex_query: Option<AqlQuery>,
+        edge_query: Option<AqlQuery>,
+        retry_policy: &RetryPolicy,
+        timeouts: &ClientTimeouts,
+        client: Option<ClientWithMiddleware>,
+    ) -> Result<Self, LoadError> {
+        if db_config.endpoints.is_empty() {
+            return Err("no endpoints given".into());
+        }
+        let client = match client {
+            Some(client) => client,
+            None => build_client(&db_config, retry_policy, timeouts)?,
+        };
+        Ok(AqlSource {
+            db_config,
+            load_config,
+            vertex_query,
+            edge_query,
+            client,
+        })
+    }
+
+    fn field_names(&self, query: &AqlQuery, load_all_attributes: bool) -> Vec<String> {
+        if load_all_attributes {
+            return vec![];
+        }
+        let mut field_names = vec!["@collection_name".to_string()];
+        field_names.extend(query.fields.iter().cloned());
+        field_names
+    }
+
+    // Runs a streaming cursor and hands every result batch to `batch_function`.
+    pub async fn for_each_batch<F>(
+        &self,
+        query: &AqlQuery,
+        mut batch_function: F,
+    ) -> Result<(), LoadError>
+    where
+        F: FnMut(Vec<Value>) -> Result<(), LoadError>,
+    {
+        let body = json!({
+            "query": query.query,
+            "bindVars": query.bind_vars,
+            "batchSize": self.load_config.batch_size,
+            // Lets a batch whose response got lost be fetched again
+            "options": {"stream": true, "allowRetry": true},
+        });
+        let url = make_url(&self.db_config, "/_api/cursor");
+        let resp = handle_auth(self.client.post(url), &self.db_config)
+            .body(body.to_string())
+            .send()
+            .await;
+        let mut cursor =
+            handle_arangodb_response_with_parsed_body::<CursorResult>(resp, StatusCode::CREATED)
+                .await
+                .map_err(|e| LoadError::from(e).context("Could not create AQL cursor"))?;
+
+        loop {
+            debug!("Received AQL batch of {} rows", cursor.result.len());
+            let has_more = cursor.has_more;
+            let id = cursor.id.take();
+            let next_batch_id = cursor.next_batch_id.take();
+            if let Err(e) = batch_function(std::mem::take(&mut cursor.result)) {
+                if let (true, Some(id)) = (has_more, id) {
+                    self.delete_cursor(&id).await;
+                }
+                return Err(e);
+            }
+
+            let id = match (has_more, id) {
+                (true, Some(id)) => id,
+                _ => return Ok(()),
+            };
+            // Asking for the batch by id makes a retried request return the
+            // same batch again. Servers before 3.11 send no id, a request
+            // retried after reaching them may skip a batch.
+            let path = match next_batch_id {
+                Some(Value::String(batch_id)) => format!("/_api/cursor/{}/{}", id, batch_id),
+                Some(Value::Number(batch_id)) => format!("/_api/cursor/{}/{}", id, batch_id),
+                _ => format!("/_api/cursor/{}", id),
+            };
+            let url = make_url(&self.db_config, &path);
+            let resp = handle_auth(self.client.post(url), &self.db_config)
+                .send()
+                .await;
+            cursor =
+                handle_arangodb_response_with_parsed_body::<CursorResult>(resp, StatusCode::OK)
+                    .await
+                    .map_err(|e| {
+                        LoadError::from(e).context(format!("Could not read AQL cursor {}", id))
+                    })?;
+        }
+    }
+
+    async fn delete_cursor(&self, id: &str) {
+        let url = make_url(&self.db_config, &format!("/_api/cursor/{}", id));
+        let resp = handle_auth(self.client.delete(url), &self.db_config)
+            .send()
+            .await;
+        if let Err(e) = resp {
+            debug!("Could not delete AQL cursor {}: {}", id, e);
+        }
+    }
+}
+
+fn into_row(value: Value) -> Result<Map<String, Value>, LoadError> {
+    match value {
+        Value::Object(row) => Ok(row),
+        other => Err(LoadError::schema(
+            None,
+            None,
+            None,
+            format!("AQL query must return objects, got: {}", other),
+        )),
+    }
+}
+
+// Attributes are requested per collection, so rows need a proper `_id`
+fn collection_of_id(id: &str) -> Result<&str, LoadError> {
+    collection_name_from_id(id).ok_or_else(|| {
+        LoadError::schema(
+            None,
+            Some(id),
+            Some("_id"),
+            format!("_id {} is not of the form <collection>/<key>", id),
+        )
+    })
+}
+
+impl GraphSource for AqlSource {
+    async fn load_vertices(&self, handler: VertexBatchHandler) -> Result<(), LoadError> {
+        let query = match &self.vertex_query {
+            Some(query) => query,
+            None => return Ok(()),
+        };
+        info!("Loading vertices from AQL query...");
+        let load_all = self.load_config.load_all_vertex_attributes;
+        let vertex_field_names = self.field_names(query, load_all);
+
+        // The cursor is the only stream, its batches come in order
+        let mut position = 0;
+        self.for_each_batch(query, |rows| {
+            let mut vertex_ids: Vec<Vec<u8>> = Vec::with_capacity(rows.len());
+            let mut vertex_json: Vec<Vec<Value>> = Vec::with_capacity(rows.len());
+            for row in rows {
+                let mut vertex = into_row(row)?;
+                let id = get_string_attribute(&vertex, "_id")?;
+                if load_all {
+                    vertex.remove("_id");
+                    vertex_json.push(vec![Value::Object(vertex)]);
+                } else {
+                    let collection_name = collection_of_id(&id)?;
+                    vertex_json.push(extract_columns(
+                        &vertex,
+                        collection_name,
+                        &vertex_field_names,
+                    ));
+                }
+                vertex_ids.push(id.into_bytes());
+            }
+            let batch_id = BatchId::new(0, position);
+            position += 1;
+            handler(batch_id, &vertex_ids, &mut vertex_json, &vertex_field_names)
+        })
+        .await
+    }
+
+    async fn load_edges(&self, handler: EdgeBatchHandler) -> Result<(), LoadError> {
+        let query = match &self.edge_query {
+            Some(query) => query,
+            None => return Ok(()),
+        };
+        info!("Loading edges from AQL query...");
+        let load_all = self.load_config.load_all_edge_attributes;
+        let edge_field_names = self.field_names(query, load_all);
+
+        let mut position = 0;
+        self.for_each_batch(query, |rows| {
+            let mut froms: Vec<Vec<u8>> = Vec::with_capacity(rows.len());
+            let mut tos: Vec<Vec<u8>> = Vec::with_capacity(rows.len());
+            let mut edge_json: Vec<Vec<Value>> = Vec::with_capacity(rows.len());
+            for row in rows {
+                let mut edge = into_row(row)?;
+                let from = get_string_attribute(&edge, "_from")?;
+                let to = get_string_attribute(&edge, "_to")?;
+                if load_all {
+                    edge.remove("_from");
+                    edge.remove("_to");
+                    edge_json.push(vec![Value::Object(edge)]);
+                } else {
+                    // The edge collection is taken from `_id`, so edge queries
+                    // have to return it unless all attributes are loaded
+                    let id = get_string_attribute(&edge, "_id")?;
+                    let collection_name = collection_of_id(&id)?;
+                    edge_json.push(extract_columns(&edge, collection_name, &edge_field_names));
+                }
+                froms.push(from.into_bytes());
+                tos.push(to.into_bytes());
+            }
+            let batch_id = BatchId::new(0, position);
+            position += 1;
+            handler(batch_id, &froms, &tos, &mut edge_json, &edge_field_names)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn collection_is_taken_from_id() {
+        assert_eq!(collection_of_id("users/1").unwrap(), "users");
+    }
+
+    #[test]
+    fn id_without_collection_is_a_schema_error() {
+        let error = collection_of_id("1").unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::Schema {
+                collection: None,
+                document_id: Some("1".to_string()),
+                attribute: Some("_id".to_string()),
+            }
+        );
+    }
+}
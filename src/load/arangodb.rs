@@ -0,0 +1,222 @@
+use crate::error::{ErrorKind, LoadError};
+use crate::load::retry::{build_client, RetryPolicy};
+use crate::load::source::{BatchId, EdgeBatchHandler, GraphSource, VertexBatchHandler};
+use crate::load::timeout::ClientTimeouts;
+use arangors_graph_exporter::errors::GraphLoaderError;
+use arangors_graph_exporter::{
+    CollectionInfo, DataLoadConfiguration, DatabaseConfiguration, GraphLoader,
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub struct ArangoDBSource {
+    graph_loader: GraphLoader,
+    has_vertex_collections: bool,
+    has_edge_collections: bool,
+    collection_names: Vec<String>,
+}
+
+impl ArangoDBSource {
+    pub async fn new(
+        db_config: DatabaseConfiguration,
+        load_config: DataLoadConfiguration,
+        vertex_collections: Vec<CollectionInfo>,
+        edge_collections: Vec<CollectionInfo>,
+        retry_policy: &RetryPolicy,
+        timeouts: &ClientTimeouts,
+        client: Option<ClientWithMiddleware>,
+    ) -> Result<Self, LoadError> {
+        if db_config.endpoints.is_empty() {
+            return Err("no endpoints given".into());
+        }
+        let client = match client {
+            Some(client) => client,
+            None => build_client(&db_config, retry_policy, timeouts)?,
+        };
+
+        let has_vertex_collections = !vertex_collections.is_empty();
+        let has_edge_collections = !edge_collections.is_empty();
+        let collection_names: Vec<String> = vertex_collections
+            .iter()
+            .chain(&edge_collections)
+            .map(|c| c.name.clone())
+            .collect();
+
+        let graph_loader = GraphLoader::new_with_client(
+            db_config,
+            load_config,
+            vertex_collections,
+            edge_collections,
+            client,
+        )
+        .await
+        .map_err(|e| {
+            with_collection_name(LoadError::from(e), &collection_names)
+                .context("Could not create graph loader")
+        })?;
+
+        Ok(ArangoDBSource {
+            graph_loader,
+            has_vertex_collections,
+            has_edge_collections,
+            collection_names,
+        })
+    }
+}
+
+// ArangoDB only names the missing collection in the error message. The
+// exporter loads all collections at once, so the name is looked up there.
+pub fn with_collection_name(mut error: LoadError, collection_names: &[String]) -> LoadError {
+    if let ErrorKind::CollectionNotFound { collection: None } = error.kind {
+        let collection = collection_names
+            .iter()
+            .find(|name| mentions(&error.message, name))
+            .cloned();
+        error.kind = ErrorKind::CollectionNotFound { collection };
+    }
+    error
+}
+
+// For requests about a single collection, which is then the missing one
+pub fn for_collection(mut error: LoadError, collection: &str) -> LoadError {
+    if let ErrorKind::CollectionNotFound { collection: None } = error.kind {
+        error.kind = ErrorKind::CollectionNotFound {
+            collection: Some(collection.to_string()),
+        };
+    }
+    error
+}
+
+// Whether `message` contains `name` other than as part of a longer name,
+// which `user` is of `user_edges`
+fn mentions(message: &str, name: &str) -> bool {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    message.match_indices(name).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        let after = message[start + name.len()..].chars().next();
+        !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+    })
+}
+
+// The exporter does not say which shard a batch came from, so all batches
+// form a single stream, numbered as they are handed in.
+#[derive(Clone, Default)]
+struct Sequence(Arc<AtomicU64>);
+
+impl Sequence {
+    fn next(&self) -> BatchId {
+        BatchId::new(0, self.0.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+// The exporter turns handler errors into strings. The first one is kept
+// here, so it can be returned with its kind once the exporter gives up.
+#[derive(Clone, Default)]
+struct HandlerError(Arc<Mutex<Option<LoadError>>>);
+
+impl HandlerError {
+    fn keep(&self, error: LoadError) -> GraphLoaderError {
+        let message = error.to_string();
+        self.0.lock().unwrap().get_or_insert(error);
+        GraphLoaderError::from(message)
+    }
+
+    fn or(&self, error: GraphLoaderError) -> LoadError {
+        self.0
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| LoadError::from(error))
+    }
+}
+
+impl GraphSource for ArangoDBSource {
+    async fn load_vertices(&self, handler: VertexBatchHandler) -> Result<(), LoadError> {
+        if !self.has_vertex_collections {
+            // only load vertices if there are any
+            return Ok(());
+        }
+        let handler_error = HandlerError::default();
+        let handler_error_clone = handler_error.clone();
+        let sequence = Sequence::default();
+        let handle_vertices =
+            move |vertex_ids: &Vec<Vec<u8>>,
+                  columns: &mut Vec<Vec<Value>>,
+                  vertex_field_names: &Vec<String>| {
+                handler(sequence.next(), vertex_ids, columns, vertex_field_names)
+                    .map_err(|e| handler_error_clone.keep(e))
+            };
+        self.graph_loader
+            .do_vertices(handle_vertices)
+            .await
+            .map_err(|e| with_collection_name(handler_error.or(e), &self.collection_names))
+    }
+
+    async fn load_edges(&self, handler: EdgeBatchHandler) -> Result<(), LoadError> {
+        if !self.has_edge_collections {
+            // only load edges if there are any
+            return Ok(());
+        }
+        let handler_error = HandlerError::default();
+        let handler_error_clone = handler_error.clone();
+        let sequence = Sequence::default();
+        let handle_edges = move |from_ids: &Vec<Vec<u8>>,
+                                 to_ids: &Vec<Vec<u8>>,
+                                 columns: &mut Vec<Vec<Value>>,
+                                 edge_field_names: &Vec<String>| {
+            handler(sequence.next(), from_ids, to_ids, columns, edge_field_names)
+                .map_err(|e| handler_error_clone.keep(e))
+        };
+        self.graph_loader
+            .do_edges(handle_edges)
+            .await
+            .map_err(|e| with_collection_name(handler_error.or(e), &self.collection_names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collection_not_found(message: &str) -> LoadError {
+        LoadError::new(ErrorKind::CollectionNotFound { collection: None }, message)
+    }
+
+    #[test]
+    fn missing_collection_is_not_matched_by_a_prefix() {
+        let names = vec!["user".to_string(), "user_edges".to_string()];
+        let error = with_collection_name(
+            collection_not_found("collection or view not found: user_edges"),
+            &names,
+        );
+        assert_eq!(
+            error.kind,
+            ErrorKind::CollectionNotFound {
+                collection: Some("user_edges".to_string())
+            }
+        );
+
+        let error = with_collection_name(
+            collection_not_found("collection user not found in shard distribution"),
+            &names,
+        );
+        assert_eq!(
+            error.kind,
+            ErrorKind::CollectionNotFound {
+                collection: Some("user".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn unmentioned_collection_is_left_unnamed() {
+        let names = vec!["user".to_string()];
+        let error = with_collection_name(collection_not_found("not found: users"), &names);
+        assert_eq!(
+            error.kind,
+            ErrorKind::CollectionNotFound { collection: None }
+        );
+    }
+}
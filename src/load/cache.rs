@@ -0,0 +1,161 @@
+use crate::error::LoadError;
+use crate::graph::Persist;
+use crate::input::load_request::DataLoadRequest;
+use crate::load::arangodb::for_collection;
+use crate::load::checkpoint::describe_request;
+use crate::load::retry::build_client;
+use crate::output::atomic::write_atomically;
+use arangors_graph_exporter::client::auth::handle_auth;
+use arangors_graph_exporter::client::make_url;
+use arangors_graph_exporter::request::handle_arangodb_response_with_parsed_body;
+use log::{info, warn};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+// Part of every key, results written by another format are never read
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+#[derive(Deserialize)]
+struct RevisionResponse {
+    revision: String,
+}
+
+// Changes with every write to the collection
+pub async fn collection_revisions(req: &DataLoadRequest) -> Result<Map<String, Value>, LoadError> {
+    if req.db_config.endpoints.is_empty() {
+        return Err("no endpoints given".into());
+    }
+    let client = match &req.client {
+        Some(client) => client.clone(),
+        None => build_client(&req.db_config, &req.retry_policy, &req.timeouts)?,
+    };
+    let collection_names: Vec<String> = req
+        .vertex_collections
+        .iter()
+        .chain(req.edge_collections.iter())
+        .map(|c| c.name.clone())
+        .collect();
+    let mut revisions = Map::new();
+    for name in &collection_names {
+        let url = make_url(
+            &req.db_config,
+            &format!("/_api/collection/{}/revision", name),
+        );
+        let resp = handle_auth(client.get(url), &req.db_config).send().await;
+        let revision =
+            handle_arangodb_response_with_parsed_body::<RevisionResponse>(resp, StatusCode::OK)
+                .await
+                .map_err(|e| {
+                    for_collection(LoadError::from(e), name)
+                        .context(format!("Could not read revision of collection {}", name))
+                })?
+                .revision;
+        revisions.insert(name.clone(), Value::String(revision));
+    }
+    Ok(revisions)
+}
+
+// Results of earlier loads, stored by a fingerprint of the request and the
+// graph they were built into, followed by one of the revisions of the loaded
+// collections. A changed collection changes the second part, so a stale
+// result is never read, and it is removed once the new one is stored.
+pub struct ResultCache {
+    directory: PathBuf,
+    request_fingerprint: String,
+    path: PathBuf,
+}
+
+fn fingerprint(value: &Value) -> String {
+    let hash = Sha256::digest(value.to_string().as_bytes());
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl ResultCache {
+    // `graph_description` is what `Persist::describe` returns
+    pub async fn open(req: &DataLoadRequest, graph_description: Value) -> Result<Self, LoadError> {
+        let directory = match &req.cache_dir {
+            Some(directory) => Path::new(directory),
+            None => return Err("no cache directory given".into()),
+        };
+        let request_fingerprint = fingerprint(&json!({
+            "version": CACHE_FORMAT_VERSION,
+            "request": describe_request(req),
+            "graph": graph_description,
+        }));
+        let revisions_fingerprint = fingerprint(&Value::Object(collection_revisions(req).await?));
+        std::fs::create_dir_all(directory).map_err(|e| {
+            LoadError::from(format!(
+                "Could not create cache directory {}: {}",
+                directory.display(),
+                e
+            ))
+        })?;
+        let path = directory.join(format!(
+            "{}-{}.graph",
+            request_fingerprint, revisions_fingerprint
+        ));
+        Ok(ResultCache {
+            directory: directory.to_path_buf(),
+            request_fingerprint,
+            path,
+        })
+    }
+
+    // Fills the graph from the cache, false if there is no result yet
+    pub fn restore(&self, graph: &mut dyn Persist) -> Result<bool, LoadError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => {
+                return Err(format!("Could not open {}: {}", self.path.display(), e).into());
+            }
+        };
+        info!("Loading cached result {}", self.path.display());
+        graph.restore_data(&mut BufReader::new(file)).map_err(|e| {
+            LoadError::from(format!("Could not read {}: {}", self.path.display(), e))
+        })?;
+        Ok(true)
+    }
+
+    // Written to a temporary file first, a failed write never leaves a
+    // partial result behind
+    pub fn store(&self, graph: &dyn Persist) -> Result<(), LoadError> {
+        write_atomically(&self.path, |writer| graph.save_data(writer)).map_err(|e| {
+            LoadError::from(format!("Could not write {}: {}", self.path.display(), e))
+        })?;
+        info!("Cached result as {}", self.path.display());
+        self.remove_stale();
+        Ok(())
+    }
+
+    // Removes the results of the same request for earlier revisions
+    fn remove_stale(&self) {
+        let prefix = format!("{}-", self.request_fingerprint);
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Could not list {}: {}", self.directory.display(), e);
+                return;
+            }
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let is_stale = path != self.path
+                && path.extension().is_some_and(|ext| ext == "graph")
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix));
+            if is_stale {
+                info!("Removing stale cached result {}", path.display());
+                if let Err(e) = std::fs::remove_file(&path) {
+                    warn!("Could not remove {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::error::LoadError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// How often a running load looks for a cancellation request
+pub const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Shared flag to stop a running load. Sources and batch handlers check it
+// cooperatively, so a load stops at the next batch boundary at the latest.
+#[derive(Clone, Default)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+    parent: Option<Arc<Cancellation>>,
+}
+
+impl Cancellation {
+    // A cancellation which is also cancelled with this one, but cancelling
+    // the child leaves this one untouched.
+    pub fn child(&self) -> Cancellation {
+        Cancellation {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            parent: Some(Arc::new(self.clone())),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+            || self.parent.as_ref().is_some_and(|p| p.is_cancelled())
+    }
+
+    pub fn check(&self) -> Result<(), LoadError> {
+        match self.is_cancelled() {
+            true => Err(LoadError::cancelled()),
+            false => Ok(()),
+        }
+    }
+
+    // Resolves once the load has been cancelled
+    pub async fn cancelled(&self) {
+        while !self.is_cancelled() {
+            tokio::time::sleep(CANCELLATION_POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,537 @@
+use crate::error::LoadError;
+use crate::input::load_request::DataLoadRequest;
+use crate::load::cache::collection_revisions;
+use crate::load::source::{BatchId, EdgeBatchHandler, GraphSource, VertexBatchHandler};
+use crate::output::atomic::write_atomically;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+// Written batches are synced and recorded in the manifest this often, a
+// crash loses at most the batches written since
+const SYNC_BATCHES: u64 = 64;
+const SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    // What was requested, a checkpoint is only resumed by the same request
+    request: Value,
+    // Of the loaded collections, a checkpoint of changed collections is
+    // not resumed. Null if the source has no revisions.
+    #[serde(default)]
+    revisions: Value,
+    completed_phases: Vec<String>,
+    // Of the phases in progress
+    #[serde(default)]
+    progress: BTreeMap<String, PhaseProgress>,
+}
+
+// The batches of a phase already on disk
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct PhaseProgress {
+    // Of the batch file, anything after it was not completely written
+    length: u64,
+    // The position of the next batch to write, by stream
+    cursors: BTreeMap<usize, u64>,
+}
+
+impl PhaseProgress {
+    fn is_written(&self, batch_id: BatchId) -> bool {
+        self.cursors
+            .get(&batch_id.stream)
+            .is_some_and(|next| batch_id.position < *next)
+    }
+}
+
+// The batches of a load kept on disk, so a failed load can be resumed.
+// The batches of a completed phase are read back instead of being fetched
+// again. An incomplete phase reads back the batches written so far and is
+// then loaded again, skipping the batches already written: a request always
+// yields the same batches under the same ids. Replaying the batches rebuilds
+// the graph, so no graph state is stored.
+pub struct Checkpoint {
+    directory: PathBuf,
+    // Shared with the writers, which record the batches they synced
+    manifest: Arc<Mutex<Manifest>>,
+}
+
+impl Checkpoint {
+    pub async fn open(directory: &str, req: &DataLoadRequest) -> Result<Self, LoadError> {
+        let request = describe_request(req);
+        // Files and query results carry no revisions
+        let revisions = if req.file_source.is_none()
+            && req.vertex_query.is_none()
+            && req.edge_query.is_none()
+        {
+            Value::Object(collection_revisions(req).await?)
+        } else {
+            Value::Null
+        };
+        let directory = PathBuf::from(directory);
+        fs::create_dir_all(&directory).map_err(|e| io_error(&directory, e))?;
+        let manifest_path = directory.join(MANIFEST_FILE);
+        let manifest = match fs::read(&manifest_path) {
+            Ok(content) => {
+                let manifest: Manifest = serde_json::from_slice(&content).map_err(|e| {
+                    format!("Invalid checkpoint {}: {}", manifest_path.display(), e)
+                })?;
+                if manifest.request != request {
+                    return Err(format!(
+                        "Checkpoint in {} belongs to a different request, remove it to start over",
+                        directory.display()
+                    )
+                    .into());
+                }
+                if manifest.revisions != revisions {
+                    return Err(format!(
+                        "Collections changed since the checkpoint in {} was written, remove it to start over",
+                        directory.display()
+                    )
+                    .into());
+                }
+                info!(
+                    "Resuming load from checkpoint {}, completed: {:?}",
+                    directory.display(),
+                    manifest.completed_phases
+                );
+                manifest
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let manifest = Manifest {
+                    request,
+                    revisions,
+                    completed_phases: vec![],
+                    progress: BTreeMap::new(),
+                };
+                write_manifest(&directory, &manifest)?;
+                manifest
+            }
+            Err(e) => return Err(io_error(&manifest_path, e)),
+        };
+        Ok(Checkpoint {
+            directory,
+            manifest: Arc::new(Mutex::new(manifest)),
+        })
+    }
+
+    fn is_completed(&self, phase: &str) -> bool {
+        let manifest = self.manifest.lock().unwrap();
+        manifest.completed_phases.iter().any(|p| p == phase)
+    }
+
+    fn progress(&self, phase: &str) -> PhaseProgress {
+        let manifest = self.manifest.lock().unwrap();
+        manifest.progress.get(phase).cloned().unwrap_or_default()
+    }
+
+    fn complete(&self, phase: &str) -> Result<(), LoadError> {
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.completed_phases.push(phase.to_string());
+        manifest.progress.remove(phase);
+        write_manifest(&self.directory, &manifest)
+    }
+
+    // Continues the batch file of a phase after the batches recorded so far
+    fn writer(&self, phase: &str, progress: &PhaseProgress) -> Result<BatchWriter, LoadError> {
+        let path = self.batch_file(phase);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| io_error(&path, e))?;
+        let file_length = file.metadata().map_err(|e| io_error(&path, e))?.len();
+        if file_length < progress.length {
+            return Err(
+                format!("Invalid checkpoint {}: batches are missing", path.display()).into(),
+            );
+        }
+        // Drops a batch which was not completely written
+        file.set_len(progress.length)
+            .map_err(|e| io_error(&path, e))?;
+        Ok(BatchWriter {
+            path,
+            phase: phase.to_string(),
+            directory: self.directory.clone(),
+            manifest: self.manifest.clone(),
+            file: Mutex::new(Some(WriterState {
+                file,
+                written: progress.clone(),
+                unsynced_batches: 0,
+                last_sync: Instant::now(),
+            })),
+        })
+    }
+
+    fn batch_file(&self, phase: &str) -> PathBuf {
+        self.directory.join(format!("{}.jsonl.gz", phase))
+    }
+
+    // Removes the checkpoint once the load it was kept for succeeded
+    pub fn remove(self) -> Result<(), LoadError> {
+        // The manifest goes first, without it the batches are never read
+        for file in [
+            self.directory.join(MANIFEST_FILE),
+            self.batch_file("vertices"),
+            self.batch_file("edges"),
+        ] {
+            match fs::remove_file(&file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(io_error(&file, e))
+                }
+                _ => {}
+            }
+        }
+        // Left alone if the directory holds anything else
+        let _ = fs::remove_dir(&self.directory);
+        Ok(())
+    }
+}
+
+// A crash never leaves half a manifest
+fn write_manifest(directory: &Path, manifest: &Manifest) -> Result<(), LoadError> {
+    let path = directory.join(MANIFEST_FILE);
+    write_atomically(&path, |writer| Ok(serde_json::to_writer(writer, manifest)?))
+        .map_err(|e| io_error(&path, e))
+}
+
+fn io_error(path: &Path, error: impl std::fmt::Display) -> LoadError {
+    LoadError::from(format!(
+        "Could not access checkpoint {}: {}",
+        path.display(),
+        error
+    ))
+}
+
+// Identifies a request. Credentials and settings not changing the loaded
+// graph are left out, so a load can be resumed with a fresh token.
+pub fn describe_request(request: &DataLoadRequest) -> Value {
+    let collections = |collections: &[arangors_graph_exporter::CollectionInfo]| {
+        collections
+            .iter()
+            .map(|c| json!({"name": c.name, "fields": c.fields}))
+            .collect::<Vec<Value>>()
+    };
+    let filters = |filters: &std::collections::HashMap<String, _>| {
+        filters
+            .iter()
+            .map(|(name, filter)| (name.clone(), Value::String(format!("{:?}", filter))))
+            .collect::<Map<String, Value>>()
+    };
+    let query = |query: &Option<crate::input::load_request::AqlQuery>| {
+        query
+            .as_ref()
+            .map(|q| json!({"query": q.query, "bind_vars": q.bind_vars, "fields": q.fields}))
+    };
+    json!({
+        "endpoints": request.db_config.endpoints,
+        "database": request.db_config.database,
+        "file_source": request.file_source.as_ref().map(|f| &f.path),
+        "vertex_collections": collections(&request.vertex_collections),
+        "edge_collections": collections(&request.edge_collections),
+        "vertex_filters": filters(&request.vertex_filters),
+        "edge_filters": filters(&request.edge_filters),
+        "vertex_query": query(&request.vertex_query),
+        "edge_query": query(&request.edge_query),
+        "load_all_vertex_attributes": request.load_config.load_all_vertex_attributes,
+        "load_all_edge_attributes": request.load_config.load_all_edge_attributes,
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct VertexBatch {
+    id: BatchId,
+    ids: Vec<String>,
+    columns: Vec<Vec<Value>>,
+    field_names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EdgeBatch {
+    id: BatchId,
+    from_ids: Vec<String>,
+    to_ids: Vec<String>,
+    columns: Vec<Vec<Value>>,
+    field_names: Vec<String>,
+}
+
+fn ids_to_strings(ids: &[Vec<u8>]) -> Result<Vec<String>, LoadError> {
+    ids.iter()
+        .map(|id| {
+            String::from_utf8(id.clone())
+                .map_err(|_| LoadError::from("Document id is not valid UTF-8"))
+        })
+        .collect()
+}
+
+fn strings_to_ids(ids: Vec<String>) -> Vec<Vec<u8>> {
+    ids.into_iter().map(String::into_bytes).collect()
+}
+
+// Appends the batches of one phase, called from several threads at once.
+// Every batch is a gzip member of its own. The batches are synced and
+// recorded in the manifest every SYNC_BATCHES batches or SYNC_INTERVAL,
+// a resume drops anything written after the recorded length.
+struct BatchWriter {
+    path: PathBuf,
+    phase: String,
+    directory: PathBuf,
+    manifest: Arc<Mutex<Manifest>>,
+    // None once the phase completed
+    file: Mutex<Option<WriterState>>,
+}
+
+struct WriterState {
+    file: File,
+    // Everything in the file, recorded or not
+    written: PhaseProgress,
+    unsynced_batches: u64,
+    last_sync: Instant,
+}
+
+impl WriterState {
+    // Syncs the file, what was written can be recorded then
+    fn sync(&mut self, path: &Path) -> Result<PhaseProgress, LoadError> {
+        self.file.sync_data().map_err(|e| io_error(path, e))?;
+        self.unsynced_batches = 0;
+        self.last_sync = Instant::now();
+        Ok(self.written.clone())
+    }
+}
+
+impl BatchWriter {
+    fn append(&self, batch_id: BatchId, batch: &impl Serialize) -> Result<(), LoadError> {
+        // Compressed outside of the lock, only writing is serialized
+        let mut line = serde_json::to_vec(batch).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        let mut encoder = GzEncoder::new(vec![], Compression::fast());
+        encoder
+            .write_all(&line)
+            .map_err(|e| io_error(&self.path, e))?;
+        let member = encoder.finish().map_err(|e| io_error(&self.path, e))?;
+
+        let synced = {
+            let mut state = self.file.lock().unwrap();
+            let state = match state.as_mut() {
+                Some(state) => state,
+                None => return Err("Checkpoint batch written after its phase completed".into()),
+            };
+            state
+                .file
+                .write_all(&member)
+                .map_err(|e| io_error(&self.path, e))?;
+            state.written.length += member.len() as u64;
+            state
+                .written
+                .cursors
+                .insert(batch_id.stream, batch_id.position + 1);
+            state.unsynced_batches += 1;
+            if state.unsynced_batches >= SYNC_BATCHES || state.last_sync.elapsed() >= SYNC_INTERVAL
+            {
+                Some(state.sync(&self.path)?)
+            } else {
+                None
+            }
+        };
+        // Recorded after the file lock is released, so writing the manifest
+        // does not hold up the other writers
+        match synced {
+            Some(synced) => self.record(synced),
+            None => Ok(()),
+        }
+    }
+
+    fn record(&self, synced: PhaseProgress) -> Result<(), LoadError> {
+        let mut manifest = self.manifest.lock().unwrap();
+        let progress = manifest.progress.entry(self.phase.clone()).or_default();
+        // Another writer may have recorded a later sync in the meantime
+        if synced.length <= progress.length {
+            return Ok(());
+        }
+        *progress = synced;
+        write_manifest(&self.directory, &manifest)
+    }
+
+    // Syncs and records the batches written since the last sync, also if
+    // the phase failed, so a resume does not fetch them again
+    fn finish(&self) -> Result<(), LoadError> {
+        let state = self.file.lock().unwrap().take();
+        match state {
+            Some(mut state) if state.unsynced_batches > 0 => {
+                let synced = state.sync(&self.path)?;
+                self.record(synced)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Reads the batches written so far on a blocking thread
+async fn replay<B: serde::de::DeserializeOwned>(
+    path: PathBuf,
+    mut handle_batch: impl FnMut(B) -> Result<(), LoadError> + Send + 'static,
+) -> Result<(), LoadError> {
+    tokio::task::spawn_blocking(move || {
+        let file = File::open(&path).map_err(|e| io_error(&path, e))?;
+        // A phase without batches leaves an empty file, which is no gzip
+        if file.metadata().map_err(|e| io_error(&path, e))?.len() == 0 {
+            return Ok(());
+        }
+        for line in BufReader::new(MultiGzDecoder::new(file)).lines() {
+            let line = line.map_err(|e| io_error(&path, e))?;
+            let batch: B = serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid checkpoint {}: {}", path.display(), e))?;
+            handle_batch(batch)?;
+        }
+        Ok(())
+    })
+    .await?
+}
+
+// Records every batch of `source` in the checkpoint, completed phases are
+// read from the checkpoint instead.
+pub struct CheckpointedSource<'a, S> {
+    source: &'a S,
+    checkpoint: &'a Checkpoint,
+}
+
+impl<'a, S> CheckpointedSource<'a, S> {
+    pub fn new(source: &'a S, checkpoint: &'a Checkpoint) -> Self {
+        CheckpointedSource { source, checkpoint }
+    }
+}
+
+impl<S: GraphSource> GraphSource for CheckpointedSource<'_, S> {
+    async fn load_vertices(&self, handler: VertexBatchHandler) -> Result<(), LoadError> {
+        let path = self.checkpoint.batch_file("vertices");
+        if self.checkpoint.is_completed("vertices") {
+            info!("Reading vertices from checkpoint {}", path.display());
+            return replay(path, move |batch: VertexBatch| {
+                let mut columns = batch.columns;
+                handler(
+                    batch.id,
+                    &strings_to_ids(batch.ids),
+                    &mut columns,
+                    &batch.field_names,
+                )
+            })
+            .await;
+        }
+
+        let progress = self.checkpoint.progress("vertices");
+        let writer = Arc::new(self.checkpoint.writer("vertices", &progress)?);
+        if progress.length > 0 {
+            info!(
+                "Reading written vertices from checkpoint {}",
+                path.display()
+            );
+            let handler = handler.clone();
+            replay(path, move |batch: VertexBatch| {
+                let mut columns = batch.columns;
+                handler(
+                    batch.id,
+                    &strings_to_ids(batch.ids),
+                    &mut columns,
+                    &batch.field_names,
+                )
+            })
+            .await?;
+        }
+
+        let writer_clone = writer.clone();
+        let recording_handler: VertexBatchHandler =
+            Arc::new(move |batch_id, vertex_ids, columns, vertex_field_names| {
+                if progress.is_written(batch_id) {
+                    return Ok(());
+                }
+                writer_clone.append(
+                    batch_id,
+                    &VertexBatch {
+                        id: batch_id,
+                        ids: ids_to_strings(vertex_ids)?,
+                        columns: columns.clone(),
+                        field_names: vertex_field_names.clone(),
+                    },
+                )?;
+                handler(batch_id, vertex_ids, columns, vertex_field_names)
+            });
+        let loaded = self.source.load_vertices(recording_handler).await;
+        // A failed load is reported over a failed sync
+        let finished = writer.finish();
+        loaded?;
+        finished?;
+        self.checkpoint.complete("vertices")
+    }
+
+    async fn load_edges(&self, handler: EdgeBatchHandler) -> Result<(), LoadError> {
+        let path = self.checkpoint.batch_file("edges");
+        if self.checkpoint.is_completed("edges") {
+            info!("Reading edges from checkpoint {}", path.display());
+            return replay(path, move |batch: EdgeBatch| {
+                let mut columns = batch.columns;
+                handler(
+                    batch.id,
+                    &strings_to_ids(batch.from_ids),
+                    &strings_to_ids(batch.to_ids),
+                    &mut columns,
+                    &batch.field_names,
+                )
+            })
+            .await;
+        }
+
+        let progress = self.checkpoint.progress("edges");
+        let writer = Arc::new(self.checkpoint.writer("edges", &progress)?);
+        if progress.length > 0 {
+            info!("Reading written edges from checkpoint {}", path.display());
+            let handler = handler.clone();
+            replay(path, move |batch: EdgeBatch| {
+                let mut columns = batch.columns;
+                handler(
+                    batch.id,
+                    &strings_to_ids(batch.from_ids),
+                    &strings_to_ids(batch.to_ids),
+                    &mut columns,
+                    &batch.field_names,
+                )
+            })
+            .await?;
+        }
+
+        let writer_clone = writer.clone();
+        let recording_handler: EdgeBatchHandler = Arc::new(
+            move |batch_id, from_ids, to_ids, columns, edge_field_names| {
+                if progress.is_written(batch_id) {
+                    return Ok(());
+                }
+                writer_clone.append(
+                    batch_id,
+                    &EdgeBatch {
+                        id: batch_id,
+                        from_ids: ids_to_strings(from_ids)?,
+                        to_ids: ids_to_strings(to_ids)?,
+                        columns: columns.clone(),
+                        field_names: edge_field_names.clone(),
+                    },
+                )?;
+                handler(batch_id, from_ids, to_ids, columns, edge_field_names)
+            },
+        );
+        let loaded = self.source.load_edges(recording_handler).await;
+        // A failed load is reported over a failed sync
+        let finished = writer.finish();
+        loaded?;
+        finished?;
+        self.checkpoint.complete("edges")
+    }
+}
@@ -0,0 +1,65 @@
+use crate::error::LoadError;
+use serde_json::{Map, Value};
+
+// Helpers for raw documents and the rows of the batches built from them.
+
+pub fn extract_columns(
+    document: &Map<String, Value>,
+    collection_name: &str,
+    field_names: &[String],
+) -> Vec<Value> {
+    field_names
+        .iter()
+        .map(|field| {
+            if field == "@collection_name" {
+                Value::String(collection_name.to_string())
+            } else {
+                document.get(field).cloned().unwrap_or(Value::Null)
+            }
+        })
+        .collect()
+}
+
+pub fn get_string_attribute(
+    document: &Map<String, Value>,
+    name: &str,
+) -> Result<String, LoadError> {
+    match document.get(name) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        _ => {
+            let document_id = document.get("_id").and_then(Value::as_str);
+            Err(LoadError::schema(
+                document_id.and_then(collection_name_from_id),
+                document_id,
+                Some(name),
+                format!(
+                    "JSON is no object with a string {} attribute:\n{}",
+                    name,
+                    Value::Object(document.clone())
+                ),
+            ))
+        }
+    }
+}
+
+pub fn collection_name_from_id(id: &str) -> Option<&str> {
+    id.split_once('/').map(|(collection, _)| collection)
+}
+
+// Finds the collection of a row handed to a batch handler, from the
+// `@collection_name` column, the given document id or the `_id` of a
+// whole document loaded with load_all_*_attributes.
+pub fn collection_name_of_row(
+    row: &[Value],
+    collection_position: Option<usize>,
+    row_id: Option<&[u8]>,
+) -> Option<String> {
+    if let Some(position) = collection_position {
+        return row.get(position)?.as_str().map(str::to_string);
+    }
+    let id = match row_id {
+        Some(id) => std::str::from_utf8(id).ok()?,
+        None => row.first()?.get("_id")?.as_str()?,
+    };
+    collection_name_from_id(id).map(str::to_string)
+}
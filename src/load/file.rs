@@ -0,0 +1,371 @@
+use crate::error::{ErrorKind, LoadError};
+use crate::input::load_request::FileSourceConfiguration;
+use crate::load::cancel::Cancellation;
+use crate::load::document::{extract_columns, get_string_attribute};
+use crate::load::source::{BatchId, EdgeBatchHandler, GraphSource, VertexBatchHandler};
+use arangors_graph_exporter::{CollectionInfo, DataLoadConfiguration};
+use flate2::read::MultiGzDecoder;
+use log::{debug, info};
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+// Older arangodump versions wrap every line in an envelope such as
+// {"type": 2300, "key": "1", "data": {...}}. 2300 marks a document,
+// everything else (e.g. 2302 for removals) carries no document data.
+const DUMP_MARKER_DOCUMENT: u64 = 2300;
+
+const DATA_FILE_SUFFIXES: [&str; 2] = [".data.json", ".jsonl"];
+
+// Reads on a blocking thread, which stops at the next batch once the load
+// is cancelled or its deadline passed.
+#[derive(Clone)]
+pub struct FileSource {
+    path: PathBuf,
+    vertex_collections: Vec<CollectionInfo>,
+    edge_collections: Vec<CollectionInfo>,
+    load_config: DataLoadConfiguration,
+    cancellation: Cancellation,
+}
+
+impl FileSource {
+    pub fn new(
+        file_source: FileSourceConfiguration,
+        load_config: DataLoadConfiguration,
+        vertex_collections: Vec<CollectionInfo>,
+        edge_collections: Vec<CollectionInfo>,
+        cancellation: Cancellation,
+    ) -> Result<Self, LoadError> {
+        let path = PathBuf::from(file_source.path);
+        if !path.is_dir() {
+            return Err(format!("File source path {} is not a directory", path.display()).into());
+        }
+        Ok(FileSource {
+            path,
+            vertex_collections,
+            edge_collections,
+            load_config,
+            cancellation,
+        })
+    }
+
+    fn read_vertices<F>(&self, mut vertices_function: F) -> Result<(), LoadError>
+    where
+        F: FnMut(
+            BatchId,
+            &Vec<Vec<u8>>,
+            &mut Vec<Vec<Value>>,
+            &Vec<String>,
+        ) -> Result<(), LoadError>,
+    {
+        let vertex_field_names = self.field_names_to_return(&self.vertex_collections);
+        let batch_size = self.load_config.batch_size as usize;
+
+        // Every collection is a stream of its own
+        for (stream, collection) in self.vertex_collections.iter().enumerate() {
+            let mut position = 0;
+            let mut vertex_ids: Vec<Vec<u8>> = Vec::with_capacity(batch_size);
+            let mut vertex_json: Vec<Vec<Value>> = Vec::with_capacity(batch_size);
+
+            for file in find_collection_files(&self.path, &collection.name)? {
+                info!("Reading vertices from {}", file.display());
+                for_each_document(&file, |mut vertex| {
+                    let id = ensure_document_id(&mut vertex, &collection.name)?;
+                    if self.load_config.load_all_vertex_attributes {
+                        vertex.remove("_id");
+                        vertex_json.push(vec![Value::Object(vertex)]);
+                    } else {
+                        vertex_json.push(extract_columns(
+                            &vertex,
+                            &collection.name,
+                            &vertex_field_names,
+                        ));
+                    }
+                    vertex_ids.push(id.into_bytes());
+
+                    if vertex_ids.len() >= batch_size {
+                        self.cancellation.check()?;
+                        vertices_function(
+                            BatchId::new(stream, position),
+                            &vertex_ids,
+                            &mut vertex_json,
+                            &vertex_field_names,
+                        )?;
+                        position += 1;
+                        vertex_ids.clear();
+                        vertex_json.clear();
+                    }
+                    Ok(())
+                })?;
+            }
+
+            if !vertex_ids.is_empty() {
+                self.cancellation.check()?;
+                vertices_function(
+                    BatchId::new(stream, position),
+                    &vertex_ids,
+                    &mut vertex_json,
+                    &vertex_field_names,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_edges<F>(&self, mut edges_function: F) -> Result<(), LoadError>
+    where
+        F: FnMut(
+            BatchId,
+            &Vec<Vec<u8>>,
+            &Vec<Vec<u8>>,
+            &mut Vec<Vec<Value>>,
+            &Vec<String>,
+        ) -> Result<(), LoadError>,
+    {
+        let edge_field_names = self.field_names_to_return(&self.edge_collections);
+        let batch_size = self.load_config.batch_size as usize;
+
+        for (stream, collection) in self.edge_collections.iter().enumerate() {
+            let mut position = 0;
+            let mut froms: Vec<Vec<u8>> = Vec::with_capacity(batch_size);
+            let mut tos: Vec<Vec<u8>> = Vec::with_capacity(batch_size);
+            let mut edge_json: Vec<Vec<Value>> = Vec::with_capacity(batch_size);
+
+            for file in find_collection_files(&self.path, &collection.name)? {
+                info!("Reading edges from {}", file.display());
+                for_each_document(&file, |mut edge| {
+                    if edge.contains_key("_key") {
+                        ensure_document_id(&mut edge, &collection.name)?;
+                    }
+                    let from = get_string_attribute(&edge, "_from")?;
+                    let to = get_string_attribute(&edge, "_to")?;
+                    if self.load_config.load_all_edge_attributes {
+                        edge.remove("_from");
+                        edge.remove("_to");
+                        edge_json.push(vec![Value::Object(edge)]);
+                    } else {
+                        edge_json.push(extract_columns(&edge, &collection.name, &edge_field_names));
+                    }
+                    froms.push(from.into_bytes());
+                    tos.push(to.into_bytes());
+
+                    if froms.len() >= batch_size {
+                        self.cancellation.check()?;
+                        edges_function(
+                            BatchId::new(stream, position),
+                            &froms,
+                            &tos,
+                            &mut edge_json,
+                            &edge_field_names,
+                        )?;
+                        position += 1;
+                        froms.clear();
+                        tos.clear();
+                        edge_json.clear();
+                    }
+                    Ok(())
+                })?;
+            }
+
+            if !froms.is_empty() {
+                self.cancellation.check()?;
+                edges_function(
+                    BatchId::new(stream, position),
+                    &froms,
+                    &tos,
+                    &mut edge_json,
+                    &edge_field_names,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // Mirrors the exporter: every document is returned with the union of the
+    // fields requested for all collections, in a stable order.
+    fn field_names_to_return(&self, collections: &[CollectionInfo]) -> Vec<String> {
+        let mut field_names: Vec<String> = vec![];
+        for field in collections.iter().flat_map(|c| c.fields.iter()) {
+            if !field_names.contains(field) {
+                field_names.push(field.clone());
+            }
+        }
+        field_names
+    }
+}
+
+impl GraphSource for FileSource {
+    async fn load_vertices(&self, handler: VertexBatchHandler) -> Result<(), LoadError> {
+        let source = self.clone();
+        tokio::task::spawn_blocking(move || {
+            source.read_vertices(|batch_id, vertex_ids, columns, vertex_field_names| {
+                handler(batch_id, vertex_ids, columns, vertex_field_names)
+            })
+        })
+        .await?
+    }
+
+    async fn load_edges(&self, handler: EdgeBatchHandler) -> Result<(), LoadError> {
+        let source = self.clone();
+        tokio::task::spawn_blocking(move || {
+            source.read_edges(|batch_id, from_ids, to_ids, columns, edge_field_names| {
+                handler(batch_id, from_ids, to_ids, columns, edge_field_names)
+            })
+        })
+        .await?
+    }
+}
+
+// arangodump does not write `_id`, so it is rebuilt from the collection and `_key`.
+fn ensure_document_id(
+    document: &mut Map<String, Value>,
+    collection_name: &str,
+) -> Result<String, LoadError> {
+    if let Some(Value::String(id)) = document.get("_id") {
+        return Ok(id.clone());
+    }
+    let id = match document.get("_key") {
+        Some(Value::String(key)) => format!("{}/{}", collection_name, key),
+        _ => {
+            return Err(LoadError::schema(
+                Some(collection_name),
+                None,
+                Some("_key"),
+                format!(
+                    "Document in collection {} has neither a string _id nor _key attribute",
+                    collection_name
+                ),
+            ))
+        }
+    };
+    document.insert("_id".to_string(), Value::String(id.clone()));
+    Ok(id)
+}
+
+fn for_each_document<F>(file: &Path, mut document_function: F) -> Result<(), LoadError>
+where
+    F: FnMut(Map<String, Value>) -> Result<(), LoadError>,
+{
+    let handle =
+        File::open(file).map_err(|e| format!("Could not open {}: {}", file.display(), e))?;
+    let reader: Box<dyn Read> = if file.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(MultiGzDecoder::new(handle))
+    } else {
+        Box::new(handle)
+    };
+
+    for (line_number, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(|e| format!("Could not read {}: {}", file.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let document: Value = serde_json::from_str(&line).map_err(|e| {
+            format!(
+                "Error parsing document in {} at line {}: {}",
+                file.display(),
+                line_number + 1,
+                e
+            )
+        })?;
+        let document = match document {
+            Value::Object(map) => map,
+            _ => {
+                return Err(format!(
+                    "Expected a JSON object in {} at line {}",
+                    file.display(),
+                    line_number + 1
+                )
+                .into())
+            }
+        };
+        if let Some(document) = unwrap_dump_envelope(document) {
+            document_function(document)?;
+        }
+    }
+    Ok(())
+}
+
+fn unwrap_dump_envelope(mut document: Map<String, Value>) -> Option<Map<String, Value>> {
+    let is_envelope = !document.contains_key("_key")
+        && document.get("type").is_some_and(Value::is_u64)
+        && document.get("data").is_some_and(Value::is_object);
+    if !is_envelope {
+        return Some(document);
+    }
+    if document["type"].as_u64() != Some(DUMP_MARKER_DOCUMENT) {
+        debug!("Skipping dump marker of type {}", document["type"]);
+        return None;
+    }
+    match document.remove("data") {
+        Some(Value::Object(data)) => Some(data),
+        _ => None,
+    }
+}
+
+// Finds the data files of a collection. Supported names are
+// `<name>.jsonl`, `<name>.data.json`, the arangodump variants
+// `<name>_<md5>.data.json` and `<name>_<md5>.<n>.data.json`,
+// each optionally gzipped.
+fn find_collection_files(
+    directory: &Path,
+    collection_name: &str,
+) -> Result<Vec<PathBuf>, LoadError> {
+    let entries = std::fs::read_dir(directory)
+        .map_err(|e| format!("Could not read {}: {}", directory.display(), e))?;
+
+    let mut files: Vec<(usize, PathBuf)> = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Could not read {}: {}", directory.display(), e))?
+            .path();
+        let file_name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name,
+            None => continue,
+        };
+        if let Some(part) = match_data_file(file_name, collection_name) {
+            files.push((part, path));
+        }
+    }
+
+    if files.is_empty() {
+        let kind = ErrorKind::CollectionNotFound {
+            collection: Some(collection_name.to_string()),
+        };
+        return Err(LoadError::new(
+            kind,
+            format!(
+                "No data files found for collection {} in {}",
+                collection_name,
+                directory.display()
+            ),
+        ));
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+// Returns the split-file number if `file_name` holds data of the collection.
+fn match_data_file(file_name: &str, collection_name: &str) -> Option<usize> {
+    let file_name = file_name.strip_suffix(".gz").unwrap_or(file_name);
+    let stem = DATA_FILE_SUFFIXES
+        .iter()
+        .find_map(|suffix| file_name.strip_suffix(suffix))?;
+
+    let (stem, part) = match stem.rsplit_once('.') {
+        Some((rest, n)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => {
+            (rest, n.parse().ok()?)
+        }
+        _ => (stem, 0),
+    };
+
+    if stem == collection_name {
+        return Some(part);
+    }
+    let hash = stem.strip_prefix(collection_name)?.strip_prefix('_')?;
+    if hash.len() == 32 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(part)
+    } else {
+        None
+    }
+}
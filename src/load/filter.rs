@@ -0,0 +1,90 @@
+use crate::input::filter::FilterExpression;
+use crate::load::document::collection_name_of_row;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Applies the per-collection filters of a request to the batches coming out
+// of a `GraphSource`, before anything is inserted into the graph.
+pub struct BatchFilter {
+    filters: HashMap<String, FilterExpression>,
+    load_all_attributes: bool,
+    // The fields the caller asked for. Filter attributes which are not part
+    // of them were only fetched to evaluate the filter and are dropped again.
+    field_names: Vec<String>,
+}
+
+impl BatchFilter {
+    pub fn new(
+        filters: HashMap<String, FilterExpression>,
+        load_all_attributes: bool,
+        field_names: Vec<String>,
+    ) -> Self {
+        BatchFilter {
+            filters,
+            load_all_attributes,
+            field_names,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    // Evaluates the filters on every row and returns which rows to keep.
+    // `row_id` gives the document `_id` of a row if it is known (vertices),
+    // otherwise the collection is taken from the row itself.
+    pub fn select<'a>(
+        &self,
+        columns: &[Vec<Value>],
+        field_names: &[String],
+        row_id: impl Fn(usize) -> Option<&'a [u8]>,
+    ) -> Vec<bool> {
+        let collection_position = field_names.iter().position(|f| f == "@collection_name");
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let collection_name = collection_name_of_row(row, collection_position, row_id(i));
+                let filter = match collection_name.and_then(|c| self.filters.get(&c)) {
+                    Some(filter) => filter,
+                    None => return true,
+                };
+                if self.load_all_attributes {
+                    let document = row.first().and_then(Value::as_object);
+                    filter.matches(&|name| document.and_then(|d| d.get(name)))
+                } else {
+                    filter.matches(&|name| {
+                        field_names
+                            .iter()
+                            .position(|f| f == name)
+                            .map(|position| &row[position])
+                    })
+                }
+            })
+            .collect()
+    }
+
+    // Removes the columns that were only loaded for filtering and returns
+    // the field names matching the remaining columns.
+    pub fn project(&self, columns: &mut [Vec<Value>], field_names: &[String]) -> Vec<String> {
+        if self.load_all_attributes || field_names.len() == self.field_names.len() {
+            return field_names.to_vec();
+        }
+        let positions: Vec<usize> = field_names
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| self.field_names.contains(f))
+            .map(|(i, _)| i)
+            .collect();
+        for row in columns.iter_mut() {
+            let old_row = std::mem::take(row);
+            *row = old_row
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| positions.contains(i))
+                .map(|(_, value)| value)
+                .collect();
+        }
+        positions.iter().map(|&i| field_names[i].clone()).collect()
+    }
+}
@@ -0,0 +1,230 @@
+use crate::error::LoadError;
+use crate::input::load_request::{AqlQuery, DataLoadRequest};
+use crate::load::aql::AqlSource;
+use crate::load::arangodb::for_collection;
+use crate::load::named_graph::resolve_named_graph;
+use crate::load::timeout::deadline_passed;
+use arangors_graph_exporter::CollectionInfo;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+// What the sampled documents hold for one attribute
+#[derive(Debug, Default)]
+pub struct AttributeSummary {
+    // Number of documents per JSON type of the attribute
+    pub types: BTreeMap<&'static str, u64>,
+    // Every length seen for array values, more than one means ragged
+    pub array_lengths: BTreeSet<usize>,
+    // Number of sampled documents without the attribute
+    pub missing: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct CollectionSummary {
+    pub count: u64,
+    pub sampled: u64,
+    pub attributes: BTreeMap<String, AttributeSummary>,
+}
+
+// The result of `inspect_graph`, nothing of the graph itself is loaded
+#[derive(Debug, Default)]
+pub struct GraphInspection {
+    pub vertex_collections: BTreeMap<String, CollectionSummary>,
+    pub edge_collections: BTreeMap<String, CollectionSummary>,
+    // (edge collection, from collection, to collection) of all edges
+    pub edge_triples: BTreeSet<(String, String, String)>,
+}
+
+// Attributes every document has, they tell nothing about the schema
+const SYSTEM_ATTRIBUTES: [&str; 5] = ["_key", "_id", "_rev", "_from", "_to"];
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl CollectionSummary {
+    // Without requested fields every attribute seen is summarized
+    fn add_sample(&mut self, document: &Map<String, Value>, fields: &[String]) {
+        self.sampled += 1;
+        let names: Vec<&String> = match fields.is_empty() {
+            true => document.keys().collect(),
+            false => fields.iter().collect(),
+        };
+        for name in names {
+            let summary = self.attributes.entry(name.clone()).or_default();
+            if let Some(value) = document.get(name) {
+                *summary.types.entry(json_type(value)).or_default() += 1;
+                if let Value::Array(values) = value {
+                    summary.array_lengths.insert(values.len());
+                }
+            }
+        }
+    }
+
+    // Every sample not counted for a type lacks the attribute
+    fn count_missing(&mut self) {
+        for summary in self.attributes.values_mut() {
+            summary.missing = self.sampled - summary.types.values().sum::<u64>();
+        }
+    }
+}
+
+fn query(query: &str, bind_vars: Value) -> AqlQuery {
+    AqlQuery {
+        query: query.to_string(),
+        bind_vars: match bind_vars {
+            Value::Object(bind_vars) => bind_vars,
+            _ => Map::new(),
+        },
+        fields: vec![],
+    }
+}
+
+async fn inspect_collection(
+    source: &AqlSource,
+    collection: &CollectionInfo,
+    load_all_attributes: bool,
+    sample_size: u64,
+) -> Result<CollectionSummary, LoadError> {
+    let mut summary = CollectionSummary::default();
+    let count_query = query(
+        "RETURN LENGTH(@@collection)",
+        json!({"@collection": collection.name}),
+    );
+    source
+        .for_each_batch(&count_query, |rows| {
+            summary.count = rows.first().and_then(Value::as_u64).unwrap_or_default();
+            Ok(())
+        })
+        .await?;
+
+    // Same fields as a load would fetch
+    let fields: Vec<String> = match load_all_attributes {
+        true => vec![],
+        false => collection.fields.clone(),
+    };
+    // Only the requested fields are sent, they may be large embeddings
+    let (projection, attributes) = match fields.is_empty() {
+        true => ("UNSET", json!(SYSTEM_ATTRIBUTES)),
+        false => ("KEEP", json!(fields)),
+    };
+    let sample_query = query(
+        &format!(
+            "FOR d IN @@collection LIMIT @sample_size RETURN {}(d, @attributes)",
+            projection
+        ),
+        json!({
+            "@collection": collection.name,
+            "sample_size": sample_size,
+            "attributes": attributes,
+        }),
+    );
+    source
+        .for_each_batch(&sample_query, |rows| {
+            for row in rows {
+                if let Value::Object(document) = row {
+                    summary.add_sample(&document, &fields);
+                }
+            }
+            Ok(())
+        })
+        .await?;
+    summary.count_missing();
+    Ok(summary)
+}
+
+async fn inspect_edge_triples(
+    source: &AqlSource,
+    collection: &CollectionInfo,
+    edge_triples: &mut BTreeSet<(String, String, String)>,
+) -> Result<(), LoadError> {
+    // Grouped on the server, only the distinct pairs are sent
+    let triple_query = query(
+        "FOR e IN @@collection
+           COLLECT from = PARSE_IDENTIFIER(e._from).collection,
+                   to = PARSE_IDENTIFIER(e._to).collection
+           RETURN [from, to]",
+        json!({"@collection": collection.name}),
+    );
+    source
+        .for_each_batch(&triple_query, |rows| {
+            for row in rows {
+                if let Some([Value::String(from), Value::String(to)]) =
+                    row.as_array().map(Vec::as_slice)
+                {
+                    edge_triples.insert((collection.name.clone(), from.clone(), to.clone()));
+                }
+            }
+            Ok(())
+        })
+        .await
+}
+
+// Counts the documents of the requested collections, summarizes the
+// requested fields of up to `sample_size` documents each and collects which
+// collections the edges connect.
+pub async fn inspect_graph(
+    req: DataLoadRequest,
+    sample_size: u64,
+) -> Result<GraphInspection, LoadError> {
+    let load_deadline = req.load_deadline;
+    tokio::select! {
+        result = inspect_collections(req, sample_size) => result,
+        error = deadline_passed(load_deadline) => Err(error),
+    }
+}
+
+async fn inspect_collections(
+    mut req: DataLoadRequest,
+    sample_size: u64,
+) -> Result<GraphInspection, LoadError> {
+    resolve_named_graph(&mut req).await?;
+    let load_all_vertex_attributes = req.load_config.load_all_vertex_attributes;
+    let load_all_edge_attributes = req.load_config.load_all_edge_attributes;
+    let source = AqlSource::new(
+        req.db_config,
+        req.load_config,
+        None,
+        None,
+        &req.retry_policy,
+        &req.timeouts,
+        req.client,
+    )?;
+
+    let mut inspection = GraphInspection::default();
+    let inspect = async {
+        for collection in &req.vertex_collections {
+            let summary =
+                inspect_collection(&source, collection, load_all_vertex_attributes, sample_size)
+                    .await
+                    .map_err(|e| for_collection(e, &collection.name))?;
+            inspection
+                .vertex_collections
+                .insert(collection.name.clone(), summary);
+        }
+        for collection in &req.edge_collections {
+            let summary =
+                inspect_collection(&source, collection, load_all_edge_attributes, sample_size)
+                    .await
+                    .map_err(|e| for_collection(e, &collection.name))?;
+            inspection
+                .edge_collections
+                .insert(collection.name.clone(), summary);
+            inspect_edge_triples(&source, collection, &mut inspection.edge_triples)
+                .await
+                .map_err(|e| for_collection(e, &collection.name))?;
+        }
+        Ok::<(), LoadError>(())
+    };
+    inspect
+        .await
+        .map_err(|e| e.context("Could not inspect graph"))?;
+    Ok(inspection)
+}
@@ -1 +1,17 @@
+pub mod aql;
+pub mod arangodb;
+pub mod cache;
+pub mod cancel;
+pub mod checkpoint;
+pub mod document;
+pub mod file;
+pub mod filter;
+pub mod inspect;
+pub mod named_graph;
+pub mod progress;
 pub mod retrieve;
+pub mod retry;
+pub mod session;
+pub mod shard;
+pub mod source;
+pub mod timeout;
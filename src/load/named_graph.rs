@@ -0,0 +1,117 @@
+use crate::error::LoadError;
+use crate::input::load_request::DataLoadRequest;
+use crate::load::retry::build_client;
+use arangors_graph_exporter::client::auth::handle_auth;
+use arangors_graph_exporter::client::make_url;
+use arangors_graph_exporter::request::handle_arangodb_response_with_parsed_body;
+use arangors_graph_exporter::CollectionInfo;
+use log::info;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct GraphResponse {
+    graph: GraphDefinition,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphDefinition {
+    edge_definitions: Vec<EdgeDefinition>,
+    #[serde(default)]
+    orphan_collections: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct EdgeDefinition {
+    collection: String,
+    from: Vec<String>,
+    to: Vec<String>,
+}
+
+// All collections of the graph, listed ones keep their fields, the others
+// are loaded without any. Listing a collection outside the graph is an
+// error, it would most likely be a typo.
+fn merge_collections(
+    graph_name: &str,
+    names: Vec<String>,
+    overrides: Vec<CollectionInfo>,
+) -> Result<Vec<CollectionInfo>, LoadError> {
+    if let Some(unknown) = overrides.iter().find(|c| !names.contains(&c.name)) {
+        return Err(format!(
+            "Collection '{}' is not part of graph '{}'",
+            unknown.name, graph_name
+        )
+        .into());
+    }
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            overrides
+                .iter()
+                .find(|c| c.name == name)
+                .cloned()
+                .unwrap_or(CollectionInfo {
+                    name,
+                    fields: vec![],
+                })
+        })
+        .collect())
+}
+
+// Fills the collections of `req` from the definition of its named graph
+pub async fn resolve_named_graph(req: &mut DataLoadRequest) -> Result<(), LoadError> {
+    let graph_name = match req.graph_name.take() {
+        Some(graph_name) => graph_name,
+        None => return Ok(()),
+    };
+    if req.db_config.endpoints.is_empty() {
+        return Err("no endpoints given".into());
+    }
+    let client = match &req.client {
+        Some(client) => client.clone(),
+        None => build_client(&req.db_config, &req.retry_policy, &req.timeouts)?,
+    };
+    // Graph names may contain characters which are not allowed in a path
+    let mut url = reqwest::Url::parse(&make_url(&req.db_config, "/_api/gharial"))
+        .map_err(|e| format!("Invalid endpoint {}: {}", req.db_config.endpoints[0], e))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Invalid endpoint {}", req.db_config.endpoints[0]))?
+        .push(&graph_name);
+    let resp = handle_auth(client.get(url), &req.db_config).send().await;
+    let definition =
+        handle_arangodb_response_with_parsed_body::<GraphResponse>(resp, StatusCode::OK)
+            .await
+            .map_err(|e| {
+                LoadError::from(e).context(format!("Could not read graph '{}'", graph_name))
+            })?
+            .graph;
+
+    let mut vertex_names: Vec<String> = vec![];
+    let mut edge_names: Vec<String> = vec![];
+    for edge_definition in definition.edge_definitions {
+        for name in edge_definition.from.into_iter().chain(edge_definition.to) {
+            if !vertex_names.contains(&name) {
+                vertex_names.push(name);
+            }
+        }
+        if !edge_names.contains(&edge_definition.collection) {
+            edge_names.push(edge_definition.collection);
+        }
+    }
+    for name in definition.orphan_collections {
+        if !vertex_names.contains(&name) {
+            vertex_names.push(name);
+        }
+    }
+    info!(
+        "Graph '{}' has vertex collections {:?} and edge collections {:?}",
+        graph_name, vertex_names, edge_names
+    );
+
+    let vertex_overrides = std::mem::take(&mut req.vertex_collections);
+    let edge_overrides = std::mem::take(&mut req.edge_collections);
+    req.vertex_collections = merge_collections(&graph_name, vertex_names, vertex_overrides)?;
+    req.edge_collections = merge_collections(&graph_name, edge_names, edge_overrides)?;
+    Ok(())
+}
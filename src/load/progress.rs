@@ -0,0 +1,99 @@
+use crate::error::LoadError;
+use crate::load::document::collection_name_of_row;
+use log::debug;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+pub const DEFAULT_PROGRESS_INTERVAL: u64 = 100000;
+
+// Called with (phase, collection name, documents processed, seconds elapsed),
+// the phase being either "vertices" or "edges".
+pub type ProgressCallback = Arc<dyn Fn(&str, &str, u64, f64) -> Result<(), String> + Send + Sync>;
+
+// Counts the documents of every collection passing through the batch
+// handlers and reports every `interval` documents per collection.
+pub struct ProgressReporter {
+    callback: Option<ProgressCallback>,
+    interval: u64,
+    start: Instant,
+    counts: Mutex<HashMap<(&'static str, String), u64>>,
+}
+
+impl ProgressReporter {
+    pub fn new(callback: Option<ProgressCallback>, interval: u64) -> Self {
+        ProgressReporter {
+            callback,
+            interval: interval.max(1),
+            start: Instant::now(),
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record<'a>(
+        &self,
+        phase: &'static str,
+        columns: &[Vec<Value>],
+        field_names: &[String],
+        row_id: impl Fn(usize) -> Option<&'a [u8]>,
+    ) -> Result<(), LoadError> {
+        let collection_position = field_names.iter().position(|f| f == "@collection_name");
+        let mut batch_counts: HashMap<String, u64> = HashMap::new();
+        for (i, row) in columns.iter().enumerate() {
+            let collection_name =
+                collection_name_of_row(row, collection_position, row_id(i)).unwrap_or_default();
+            *batch_counts.entry(collection_name).or_default() += 1;
+        }
+
+        let mut to_report: Vec<(String, u64)> = vec![];
+        {
+            let mut counts = self.counts.lock().unwrap();
+            for (collection_name, n) in batch_counts {
+                let count = counts.entry((phase, collection_name.clone())).or_default();
+                let before = *count;
+                *count += n;
+                if *count / self.interval > before / self.interval {
+                    to_report.push((collection_name, *count));
+                }
+            }
+        }
+        // Report outside of the lock, the callback may take a while
+        for (collection_name, count) in to_report {
+            self.report(phase, &collection_name, count)?;
+        }
+        Ok(())
+    }
+
+    // Reports the final count of every collection of the phase.
+    pub fn finish(&self, phase: &'static str) -> Result<(), LoadError> {
+        let mut totals: Vec<(String, u64)> = self
+            .counts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((p, _), _)| *p == phase)
+            .map(|((_, collection_name), count)| (collection_name.clone(), *count))
+            .collect();
+        totals.sort();
+        for (collection_name, count) in totals {
+            if count % self.interval != 0 {
+                self.report(phase, &collection_name, count)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn report(&self, phase: &str, collection_name: &str, count: u64) -> Result<(), LoadError> {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        debug!(
+            "Loaded {} {} from {} after {:.1}s",
+            count, phase, collection_name, elapsed
+        );
+        match &self.callback {
+            Some(callback) => callback(phase, collection_name, count, elapsed)
+                .map_err(|e| LoadError::from(format!("Progress callback failed: {}", e))),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,46 +1,113 @@
+use crate::error::LoadError;
 use crate::graph::Graph;
+use crate::input::filter::FilterExpression;
 use crate::input::load_request::DataLoadRequest;
-use arangors_graph_exporter::errors::GraphLoaderError;
-use arangors_graph_exporter::{CollectionInfo, GraphLoader};
+use crate::load::aql::AqlSource;
+use crate::load::arangodb::ArangoDBSource;
+use crate::load::cache::ResultCache;
+use crate::load::cancel::{Cancellation, CANCELLATION_POLL_INTERVAL};
+use crate::load::checkpoint::{Checkpoint, CheckpointedSource};
+use crate::load::file::FileSource;
+use crate::load::filter::BatchFilter;
+use crate::load::named_graph::resolve_named_graph;
+use crate::load::progress::ProgressReporter;
+use crate::load::shard::StreamShards;
+use crate::load::source::{EdgeBatchHandler, GraphSource, VertexBatchHandler};
+use crate::load::timeout::deadline_passed;
+use arangors_graph_exporter::CollectionInfo;
+use log::warn;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::runtime::Runtime;
 
+// Blocks until the load running on `runtime` is done. `interrupted` is
+// polled meanwhile, returning true cancels the load.
 pub fn get_arangodb_graph<G: Graph + Send + Sync + 'static>(
     req: DataLoadRequest,
-    graph_factory: impl Fn() -> Arc<RwLock<G>>,
-) -> Result<G, String> {
+    graph_factory: impl FnOnce() -> Arc<RwLock<G>>,
+    runtime: &Runtime,
+    cancellation: Cancellation,
+    interrupted: impl Fn() -> bool,
+) -> Result<G, LoadError> {
     let graph = graph_factory();
-    let graph_clone = graph.clone(); // for background thread
+    let graph_clone = graph.clone(); // for the runtime
+    let cancellation_clone = cancellation.clone();
 
-    // Fetch from ArangoDB in a background thread:
-    let handle = std::thread::spawn(move || {
+    let handle = runtime.spawn(async move {
+        fetch_graph_until_cancelled(req, graph_clone, &cancellation_clone).await
+    });
+    while !handle.is_finished() {
+        if !cancellation.is_cancelled() && interrupted() {
+            cancellation.cancel();
+        }
+        std::thread::sleep(CANCELLATION_POLL_INTERVAL);
+    }
+    runtime.block_on(handle)??;
+    unwrap_graph(graph)
+}
+
+// Shared by all loads running in the background, created on first use
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+pub fn shared_runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
         tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
             .unwrap()
-            .block_on(async { fetch_graph_from_arangodb_local_variant(req, graph_clone).await })
-    });
-    handle.join().map_err(|_s| "Computation failed")??;
+    })
+}
+
+// Runs on the caller's tokio runtime instead of a dedicated thread and runtime.
+pub async fn get_arangodb_graph_async<G: Graph + Send + Sync + 'static>(
+    req: DataLoadRequest,
+    graph_factory: impl FnOnce() -> Arc<RwLock<G>>,
+    cancellation: Cancellation,
+) -> Result<G, LoadError> {
+    let graph = graph_factory();
+    fetch_graph_until_cancelled(req, graph.clone(), &cancellation).await?;
+    unwrap_graph(graph)
+}
+
+async fn fetch_graph_until_cancelled<G: Graph + Send + Sync + 'static>(
+    req: DataLoadRequest,
+    graph_arc: Arc<RwLock<G>>,
+    cancellation: &Cancellation,
+) -> Result<Arc<RwLock<G>>, LoadError> {
+    let load_deadline = req.load_deadline;
+    // Cancelled when the deadline passes, stopping the work done off the
+    // runtime. The caller's cancellation stays untouched, so the load is
+    // reported as timed out and not as cancelled.
+    let load_cancellation = cancellation.child();
+    let fetch = fetch_graph_from_arangodb_local_variant(req, graph_arc, &load_cancellation);
+    // Dropping the fetch future aborts all outstanding requests
+    tokio::select! {
+        result = fetch => result,
+        _ = cancellation.cancelled() => Err(LoadError::cancelled()),
+        error = deadline_passed(load_deadline) => {
+            load_cancellation.cancel();
+            Err(error)
+        }
+    }
+}
+
+pub fn unwrap_graph<G>(graph: Arc<RwLock<G>>) -> Result<G, LoadError> {
     let inner_rw_lock = Arc::<std::sync::RwLock<G>>::try_unwrap(graph)
-        .map_err(|_| "Computation failed: thread failed - poisoned arc".to_string())?;
+        .map_err(|_| LoadError::from("Computation failed: thread failed - poisoned arc"))?;
     inner_rw_lock.into_inner().map_err(|poisoned_lock| {
-        format!(
+        LoadError::from(format!(
             "Computation failed: thread failed - poisoned lock {}",
             poisoned_lock
                 .source()
                 .map_or(String::from(""), <dyn Error>::to_string)
-        )
+        ))
     })
 }
 
-pub async fn fetch_graph_from_arangodb_local_variant<G: Graph + Send + Sync + 'static>(
-    req: DataLoadRequest,
-    graph_arc: Arc<RwLock<G>>,
-) -> Result<Arc<RwLock<G>>, String> {
-    let db_config = req.db_config;
-    let load_config = req.load_config;
-
+fn get_collection_infos(req: &DataLoadRequest) -> (Vec<CollectionInfo>, Vec<CollectionInfo>) {
+    let load_config = &req.load_config;
     let mut local_vertex_collections = vec![];
     let mut local_edge_collections = vec![];
 
@@ -69,84 +136,558 @@ pub async fn fetch_graph_from_arangodb_local_variant<G: Graph + Send + Sync + 's
         local_edge_collections.push(e_collection_info);
     }
 
-    if db_config.endpoints.is_empty() {
-        return Err("no endpoints given".to_string());
+    (local_vertex_collections, local_edge_collections)
+}
+
+// Builds the filters of a request. The attributes a filter reads are added
+// to the fields of its collection, unless all attributes are loaded anyway.
+fn get_batch_filters(
+    req: &DataLoadRequest,
+    vertex_collections: &mut [CollectionInfo],
+    edge_collections: &mut [CollectionInfo],
+) -> (BatchFilter, BatchFilter) {
+    let vertex_filter = BatchFilter::new(
+        req.vertex_filters.clone(),
+        req.load_config.load_all_vertex_attributes,
+        add_filter_attributes(vertex_collections, &req.vertex_filters),
+    );
+    let edge_filter = BatchFilter::new(
+        req.edge_filters.clone(),
+        req.load_config.load_all_edge_attributes,
+        add_filter_attributes(edge_collections, &req.edge_filters),
+    );
+    (vertex_filter, edge_filter)
+}
+
+// Returns the requested field names before the filter attributes were added.
+fn add_filter_attributes(
+    collections: &mut [CollectionInfo],
+    filters: &HashMap<String, FilterExpression>,
+) -> Vec<String> {
+    let mut requested_fields: Vec<String> = vec![];
+    for field in collections.iter().flat_map(|c| c.fields.iter()) {
+        if !requested_fields.contains(field) {
+            requested_fields.push(field.clone());
+        }
+    }
+    for collection in collections.iter_mut() {
+        // Without any fields all attributes are loaded
+        if collection.fields.is_empty() {
+            continue;
+        }
+        if let Some(filter) = filters.get(&collection.name) {
+            for attribute in filter.attributes() {
+                if !collection.fields.contains(&attribute) {
+                    collection.fields.push(attribute);
+                }
+            }
+        }
     }
+    requested_fields
+}
 
-    let graph_loader_res = GraphLoader::new_custom(
-        db_config,
-        load_config,
-        local_vertex_collections,
-        local_edge_collections,
-    )
-    .await;
+fn insert_vertex_batch<G: Graph>(
+    graph: &G,
+    shard: &mut G::VertexShard,
+    vertex_ids: &[Vec<u8>],
+    columns: &mut [Vec<Value>],
+    vertex_field_names: &[String],
+    keep: Option<&[bool]>,
+) -> Result<(), LoadError> {
+    for i in 0..vertex_ids.len() {
+        if keep.is_some_and(|keep| !keep[i]) {
+            continue;
+        }
+        let k = &vertex_ids[i];
+        let mut cols: Vec<Value> = vec![];
+        std::mem::swap(&mut cols, &mut columns[i]);
+        graph
+            .insert_vertex(shard, k.clone(), cols, vertex_field_names)
+            .map_err(|e| LoadError::from(e).context("Could not insert vertex"))?;
+    }
+    Ok(())
+}
 
-    let graph_loader = match graph_loader_res {
-        Ok(g) => g,
-        Err(e) => return Err(format!("Could not create graph loader: {:?}", e)),
-    };
+fn insert_edge_batch<G: Graph>(
+    graph: &G,
+    shard: &mut G::EdgeShard,
+    from_ids: &[Vec<u8>],
+    to_ids: &[Vec<u8>],
+    columns: &[Vec<Value>],
+    edge_field_names: &[String],
+    keep: Option<&[bool]>,
+) -> Result<(), LoadError> {
+    for i in 0..from_ids.len() {
+        if keep.is_some_and(|keep| !keep[i]) {
+            continue;
+        }
+        graph
+            .insert_edge(
+                shard,
+                from_ids[i].clone(),
+                to_ids[i].clone(),
+                columns[i].clone(),
+                edge_field_names,
+            )
+            .map_err(|e| LoadError::from(e).context("Could not insert edge"))?;
+    }
+    Ok(())
+}
 
-    let graph_arc_clone = graph_arc.clone();
-    let handle_vertices = move |vertex_ids: &Vec<Vec<u8>>,
-                                columns: &mut Vec<Vec<Value>>,
-                                vertex_field_names: &Vec<String>| {
-        let mut graph = graph_arc_clone.write().unwrap();
+// Everything applied to the batches of a load, independent of its source
+pub struct BatchHooks {
+    pub vertex_filter: BatchFilter,
+    pub edge_filter: BatchFilter,
+    pub cancellation: Cancellation,
+    pub progress: ProgressReporter,
+}
 
-        for i in 0..vertex_ids.len() {
-            let k = &vertex_ids[i];
-            let mut cols: Vec<Value> = vec![];
-            std::mem::swap(&mut cols, &mut columns[i]);
-            graph.insert_vertex(k.clone(), cols, vertex_field_names);
+pub async fn fetch_graph_from_arangodb_local_variant<G: Graph + Send + Sync + 'static>(
+    mut req: DataLoadRequest,
+    graph_arc: Arc<RwLock<G>>,
+    cancellation: &Cancellation,
+) -> Result<Arc<RwLock<G>>, LoadError> {
+    resolve_named_graph(&mut req).await?;
+    let cache = match req.cache_dir {
+        Some(_) => {
+            let graph_description = match graph_arc.read().unwrap().persist() {
+                Some(graph) => graph.describe(),
+                None => return Err("Results of this format cannot be cached".into()),
+            };
+            Some(Arc::new(ResultCache::open(&req, graph_description).await?))
         }
+        None => None,
+    };
+    if let Some(cache) = &cache {
+        let restored = with_persisted_graph(&graph_arc, cache, |cache, graph| {
+            cache.restore(graph.persist_mut().unwrap())
+        })
+        .await?;
+        if restored {
+            return Ok(graph_arc);
+        }
+    }
+    let graph_arc = fetch_graph_uncached(req, graph_arc, cancellation).await?;
+    if let Some(cache) = &cache {
+        // The graph was loaded, it is returned even if it cannot be cached
+        let stored = with_persisted_graph(&graph_arc, cache, |cache, graph| {
+            cache.store(graph.persist().unwrap())
+        })
+        .await;
+        if let Err(e) = stored {
+            warn!("Could not cache result: {}", e);
+        }
+    }
+    Ok(graph_arc)
+}
 
-        Ok(())
+// Reading and writing cached results is blocking file access
+async fn with_persisted_graph<G: Graph + Send + Sync + 'static, T: Send + 'static>(
+    graph_arc: &Arc<RwLock<G>>,
+    cache: &Arc<ResultCache>,
+    f: impl FnOnce(&ResultCache, &mut G) -> Result<T, LoadError> + Send + 'static,
+) -> Result<T, LoadError> {
+    let graph_arc = graph_arc.clone();
+    let cache = cache.clone();
+    tokio::task::spawn_blocking(move || f(&cache, &mut graph_arc.write().unwrap())).await?
+}
+
+async fn fetch_graph_uncached<G: Graph + Send + Sync + 'static>(
+    mut req: DataLoadRequest,
+    graph_arc: Arc<RwLock<G>>,
+    cancellation: &Cancellation,
+) -> Result<Arc<RwLock<G>>, LoadError> {
+    let (mut local_vertex_collections, mut local_edge_collections) = get_collection_infos(&req);
+    let (vertex_filter, edge_filter) = get_batch_filters(
+        &req,
+        &mut local_vertex_collections,
+        &mut local_edge_collections,
+    );
+    let hooks = Arc::new(BatchHooks {
+        vertex_filter,
+        edge_filter,
+        cancellation: cancellation.clone(),
+        progress: ProgressReporter::new(req.progress_callback.take(), req.progress_interval),
+    });
+    let checkpoint = match &req.checkpoint_dir {
+        Some(directory) => Some(Checkpoint::open(directory, &req).await?),
+        None => None,
     };
 
-    if !req.vertex_collections.is_empty() {
-        // only load vertices if there are any
-        let vertices_result = graph_loader.do_vertices(handle_vertices).await;
-        if vertices_result.is_err() {
-            return Err(format!(
-                "Could not load vertices: {:?}",
-                vertices_result.err()
-            ));
+    if req.vertex_query.is_some() || req.edge_query.is_some() {
+        let source = AqlSource::new(
+            req.db_config,
+            req.load_config,
+            req.vertex_query,
+            req.edge_query,
+            &req.retry_policy,
+            &req.timeouts,
+            req.client,
+        )?;
+        return fetch_graph_checkpointed(&source, graph_arc, hooks, checkpoint).await;
+    }
+
+    match req.file_source {
+        Some(file_source) => {
+            let source = FileSource::new(
+                file_source,
+                req.load_config,
+                local_vertex_collections,
+                local_edge_collections,
+                cancellation.clone(),
+            )?;
+            fetch_graph_checkpointed(&source, graph_arc, hooks, checkpoint).await
+        }
+        None => {
+            let source = ArangoDBSource::new(
+                req.db_config,
+                req.load_config,
+                local_vertex_collections,
+                local_edge_collections,
+                &req.retry_policy,
+                &req.timeouts,
+                req.client,
+            )
+            .await?;
+            fetch_graph_checkpointed(&source, graph_arc, hooks, checkpoint).await
         }
     }
+}
+
+// Loads through the checkpoint if there is one, it is removed once the
+// graph is complete
+async fn fetch_graph_checkpointed<S: GraphSource, G: Graph + Send + Sync + 'static>(
+    source: &S,
+    graph_arc: Arc<RwLock<G>>,
+    hooks: Arc<BatchHooks>,
+    checkpoint: Option<Checkpoint>,
+) -> Result<Arc<RwLock<G>>, LoadError> {
+    let checkpoint = match checkpoint {
+        Some(checkpoint) => checkpoint,
+        None => return fetch_graph_from_source(source, graph_arc, hooks).await,
+    };
+    let source = CheckpointedSource::new(source, &checkpoint);
+    let graph_arc = fetch_graph_from_source(&source, graph_arc, hooks).await?;
+    checkpoint.remove()?;
+    Ok(graph_arc)
+}
+
+// An edge batch as received, kept until all vertices are in the graph
+struct RawEdgeBatch {
+    from_ids: Vec<Vec<u8>>,
+    to_ids: Vec<Vec<u8>>,
+    columns: Vec<Vec<Value>>,
+    field_names: Vec<String>,
+    keep: Option<Vec<bool>>,
+}
+
+// Inserts the buffered edges of one stream into a new shard. Edges to or
+// from vertices rejected by a filter are dropped here, as the vertices are
+// only all known now.
+fn resolve_edge_batches<G: Graph>(
+    graph: &G,
+    rejected: &HashSet<Vec<u8>>,
+    batches: Vec<RawEdgeBatch>,
+    cancellation: &Cancellation,
+) -> Result<G::EdgeShard, LoadError> {
+    let mut shard = G::EdgeShard::default();
+    for batch in batches {
+        cancellation.check()?;
+        let keep = match batch.keep {
+            None if rejected.is_empty() => None,
+            keep => Some(
+                (0..batch.from_ids.len())
+                    .map(|i| {
+                        keep.as_ref().is_none_or(|keep| keep[i])
+                            && !rejected.contains(&batch.from_ids[i])
+                            && !rejected.contains(&batch.to_ids[i])
+                    })
+                    .collect::<Vec<bool>>(),
+            ),
+        };
+        insert_edge_batch(
+            graph,
+            &mut shard,
+            &batch.from_ids,
+            &batch.to_ids,
+            &batch.columns,
+            &batch.field_names,
+            keep.as_deref(),
+        )?;
+    }
+    Ok(shard)
+}
+
+pub async fn fetch_graph_from_source<S: GraphSource, G: Graph + Send + Sync + 'static>(
+    source: &S,
+    graph_arc: Arc<RwLock<G>>,
+    hooks: Arc<BatchHooks>,
+) -> Result<Arc<RwLock<G>>, LoadError> {
+    // Ids of vertices rejected by a filter. Edges to or from them are dropped,
+    // so both graph builders see the same edges. This set holds every id the
+    // filter rejects until the edges are resolved, so a filter that drops most
+    // of a large collection costs about as much memory as loading its ids.
+    let rejected_vertices: Arc<RwLock<HashSet<Vec<u8>>>> = Arc::new(RwLock::new(HashSet::new()));
+    // Batches only read the graph, it is written once per phase when the
+    // shards are merged.
+    let vertex_shards: Arc<StreamShards<G::VertexShard>> = Arc::new(StreamShards::default());
+    // Edges are fetched while vertices are still coming in, so they can
+    // only be resolved against them afterwards.
+    let raw_edge_shards: Arc<StreamShards<Vec<RawEdgeBatch>>> = Arc::new(StreamShards::default());
 
     let graph_arc_clone = graph_arc.clone();
-    let handle_edges = move |from_ids: &Vec<Vec<u8>>,
-                             to_ids: &Vec<Vec<u8>>,
-                             columns: &mut Vec<Vec<Value>>,
-                             edge_field_names: &Vec<String>| {
-        {
-            // Now actually insert edges by writing the graph
-            // object:
-            let mut graph = graph_arc_clone.write().unwrap();
-            for i in 0..from_ids.len() {
-                let insertion_result = graph.insert_edge(
-                    from_ids[i].clone(),
-                    to_ids[i].clone(),
-                    columns[i].clone(),
-                    edge_field_names,
-                );
-                if insertion_result.is_err() {
-                    return Err(GraphLoaderError::from(format!(
-                        "Could not insert edge: {:?}",
-                        insertion_result.err()
-                    )));
+    let rejected_vertices_clone = rejected_vertices.clone();
+    let vertex_shards_clone = vertex_shards.clone();
+    let hooks_clone = hooks.clone();
+    let handle_vertices: VertexBatchHandler =
+        Arc::new(move |batch_id, vertex_ids, columns, vertex_field_names| {
+            let hooks = &hooks_clone;
+            hooks.cancellation.check()?;
+            hooks
+                .progress
+                .record("vertices", columns, vertex_field_names, |i| {
+                    Some(vertex_ids[i].as_slice())
+                })?;
+            let graph = graph_arc_clone.read().unwrap();
+            if hooks.vertex_filter.is_empty() {
+                return vertex_shards_clone.with_shard(batch_id.stream, |shard| {
+                    insert_vertex_batch(
+                        &*graph,
+                        shard,
+                        vertex_ids,
+                        columns,
+                        vertex_field_names,
+                        None,
+                    )
+                });
+            }
+            let keep = hooks
+                .vertex_filter
+                .select(columns, vertex_field_names, |i| {
+                    Some(vertex_ids[i].as_slice())
+                });
+            let vertex_field_names = hooks.vertex_filter.project(columns, vertex_field_names);
+            {
+                let mut rejected = rejected_vertices_clone.write().unwrap();
+                for (id, _) in vertex_ids.iter().zip(&keep).filter(|(_, k)| !**k) {
+                    rejected.insert(id.clone());
                 }
             }
-        }
-        Ok(())
+            vertex_shards_clone.with_shard(batch_id.stream, |shard| {
+                insert_vertex_batch(
+                    &*graph,
+                    shard,
+                    vertex_ids,
+                    columns,
+                    &vertex_field_names,
+                    Some(&keep),
+                )
+            })
+        });
+
+    let raw_edge_shards_clone = raw_edge_shards.clone();
+    let hooks_clone = hooks.clone();
+    let handle_edges: EdgeBatchHandler = Arc::new(
+        move |batch_id, from_ids, to_ids, columns, edge_field_names| {
+            let hooks = &hooks_clone;
+            hooks.cancellation.check()?;
+            hooks
+                .progress
+                .record("edges", columns, edge_field_names, |_| None)?;
+            let (keep, edge_field_names) = match hooks.edge_filter.is_empty() {
+                true => (None, edge_field_names.clone()),
+                false => (
+                    Some(
+                        hooks
+                            .edge_filter
+                            .select(columns, edge_field_names, |_| None),
+                    ),
+                    hooks.edge_filter.project(columns, edge_field_names),
+                ),
+            };
+            let batch = RawEdgeBatch {
+                from_ids: from_ids.clone(),
+                to_ids: to_ids.clone(),
+                columns: std::mem::take(columns),
+                field_names: edge_field_names,
+                keep,
+            };
+            raw_edge_shards_clone.with_shard(batch_id.stream, |shard| shard.push(batch));
+            Ok(())
+        },
+    );
+
+    let load_vertices = async {
+        source
+            .load_vertices(handle_vertices)
+            .await
+            .map_err(|e| e.context("Could not load vertices"))?;
+        hooks.progress.finish("vertices")
+    };
+    let load_edges = async {
+        source
+            .load_edges(handle_edges)
+            .await
+            .map_err(|e| e.context("Could not load edges"))?;
+        hooks.progress.finish("edges")
     };
+    // The first error drops the other load, aborting its requests
+    tokio::try_join!(load_vertices, load_edges)?;
 
-    if !req.edge_collections.is_empty() {
-        // only load edges if there are any
-        let edges_result = graph_loader.do_edges(handle_edges).await;
-        if edges_result.is_err() {
-            return Err(format!("Could not load edges: {:?}", edges_result.err()));
-        }
+    graph_arc
+        .write()
+        .unwrap()
+        .merge_vertices(vertex_shards.take())
+        .map_err(|e| LoadError::from(e).context("Could not insert vertex"))?;
+
+    // Every stream's edges are resolved on their own thread, the shards are
+    // merged in stream order
+    let resolvers: Vec<_> = raw_edge_shards
+        .take()
+        .into_iter()
+        .map(|batches| {
+            let graph_arc = graph_arc.clone();
+            let rejected_vertices = rejected_vertices.clone();
+            let hooks = hooks.clone();
+            tokio::task::spawn_blocking(move || {
+                resolve_edge_batches(
+                    &*graph_arc.read().unwrap(),
+                    &rejected_vertices.read().unwrap(),
+                    batches,
+                    &hooks.cancellation,
+                )
+            })
+        })
+        .collect();
+    let mut edge_shards = vec![];
+    for resolver in resolvers {
+        let edge_shard = resolver
+            .await?
+            .map_err(|e| e.context("Could not load edges"))?;
+        edge_shards.push(edge_shard);
     }
+    graph_arc
+        .write()
+        .unwrap()
+        .merge_edges(edge_shards)
+        .map_err(|e| LoadError::from(e).context("Could not insert edge"))?;
 
     Ok(graph_arc)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{NetworkXGraph, NumpyGraph};
+    use crate::load::source::BatchId;
+    use serde_json::json;
+    use std::time::Duration;
+
+    const STREAMS: usize = 4;
+    const BATCHES: u64 = 6;
+    const BATCH_SIZE: usize = 5;
+
+    // Hands in the batches of every stream from a thread of its own. The
+    // pauses depend on `seed`, so the streams interleave differently.
+    struct InterleavingSource {
+        seed: u64,
+    }
+
+    impl InterleavingSource {
+        fn for_each_batch(&self, handle_batch: impl Fn(BatchId) -> Result<(), LoadError> + Sync) {
+            std::thread::scope(|scope| {
+                for stream in 0..STREAMS {
+                    let handle_batch = &handle_batch;
+                    scope.spawn(move || {
+                        for position in 0..BATCHES {
+                            let pause = (self.seed * 7 + stream as u64 * 13 + position * 5) % 4;
+                            std::thread::sleep(Duration::from_millis(pause));
+                            handle_batch(BatchId::new(stream, position)).unwrap();
+                        }
+                    });
+                }
+            });
+        }
+    }
+
+    fn vertex_id(stream: usize, position: u64, row: usize) -> Vec<u8> {
+        format!("user/{}_{}_{}", stream, position, row).into_bytes()
+    }
+
+    impl GraphSource for InterleavingSource {
+        async fn load_vertices(&self, handler: VertexBatchHandler) -> Result<(), LoadError> {
+            let field_names = vec!["@collection_name".to_string(), "x".to_string()];
+            self.for_each_batch(|batch_id| {
+                let ids: Vec<Vec<u8>> = (0..BATCH_SIZE)
+                    .map(|row| vertex_id(batch_id.stream, batch_id.position, row))
+                    .collect();
+                let mut columns = (0..BATCH_SIZE)
+                    .map(|row| vec![json!("user"), json!([row as f64])])
+                    .collect();
+                handler(batch_id, &ids, &mut columns, &field_names)
+            });
+            Ok(())
+        }
+
+        async fn load_edges(&self, handler: EdgeBatchHandler) -> Result<(), LoadError> {
+            let field_names = vec!["@collection_name".to_string()];
+            self.for_each_batch(|batch_id| {
+                // Every edge points to a vertex of the next stream
+                let from_ids: Vec<Vec<u8>> = (0..BATCH_SIZE)
+                    .map(|row| vertex_id(batch_id.stream, batch_id.position, row))
+                    .collect();
+                let to_ids: Vec<Vec<u8>> = (0..BATCH_SIZE)
+                    .map(|row| vertex_id((batch_id.stream + 1) % STREAMS, batch_id.position, row))
+                    .collect();
+                let mut columns = vec![vec![json!("knows")]; BATCH_SIZE];
+                handler(batch_id, &from_ids, &to_ids, &mut columns, &field_names)
+            });
+            Ok(())
+        }
+    }
+
+    fn load<G: Graph + Send + Sync + 'static>(seed: u64, graph: Arc<RwLock<G>>) -> G {
+        let hooks = Arc::new(BatchHooks {
+            vertex_filter: BatchFilter::new(HashMap::new(), false, vec![]),
+            edge_filter: BatchFilter::new(HashMap::new(), false, vec![]),
+            cancellation: Cancellation::default(),
+            progress: ProgressReporter::new(None, 1000),
+        });
+        let runtime = Runtime::new().unwrap();
+        let graph = runtime
+            .block_on(fetch_graph_from_source(
+                &InterleavingSource { seed },
+                graph,
+                hooks,
+            ))
+            .unwrap();
+        unwrap_graph(graph).unwrap()
+    }
+
+    #[test]
+    fn loads_are_deterministic_across_interleavings() {
+        let first = load(0, NumpyGraph::new());
+        assert_eq!(
+            first.cols_to_keys_to_inds["user"].len(),
+            STREAMS * BATCHES as usize * BATCH_SIZE
+        );
+        for seed in 1..4 {
+            let other = load(seed, NumpyGraph::new());
+            assert_eq!(first.cols_to_keys_to_inds, other.cols_to_keys_to_inds);
+            assert_eq!(first.coo_by_from_edge_to, other.coo_by_from_edge_to);
+        }
+    }
+
+    #[test]
+    fn networkx_edges_are_deterministic_across_interleavings() {
+        let multigraph = || NetworkXGraph::new(false, true, false, false, true, true, false);
+        let first = load(0, multigraph());
+        assert_eq!(first.coo.0.len(), STREAMS * BATCHES as usize * BATCH_SIZE);
+        for seed in 1..4 {
+            let other = load(seed, multigraph());
+            assert_eq!(first.vertex_id_to_index, other.vertex_id_to_index);
+            assert_eq!(first.coo, other.coo);
+            assert_eq!(first.edge_indices, other.edge_indices);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::{ErrorKind, LoadError};
+use crate::load::timeout::ClientTimeouts;
+use arangors_graph_exporter::DatabaseConfiguration;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::RetryTransientMiddleware;
+use reqwest_retry::{default_on_request_failure, Retryable, RetryableStrategy};
+use std::time::Duration;
+
+// How failed requests are retried. The first attempt counts towards
+// `max_attempts`, the backoff doubles from `initial_backoff` on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    // Responses with one of these are retried, besides dropped connections
+    pub retryable_status_codes: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    // The exporter's own policy: 5 retries between 30ms and 3s
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(30),
+            max_backoff: Duration::from_secs(3),
+            retryable_status_codes: vec![408, 429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn middleware(&self) -> RetryTransientMiddleware<ExponentialBackoff, RetryableStatusCodes> {
+        let backoff = ExponentialBackoff::builder()
+            .retry_bounds(self.initial_backoff, self.max_backoff)
+            .build_with_max_retries(self.max_attempts.saturating_sub(1));
+        let strategy = RetryableStatusCodes(self.retryable_status_codes.clone());
+        RetryTransientMiddleware::new_with_policy_and_strategy(backoff, strategy)
+    }
+}
+
+// Retries the configured status codes, gives up on every other error status
+struct RetryableStatusCodes(Vec<u16>);
+
+impl RetryableStrategy for RetryableStatusCodes {
+    fn handle(
+        &self,
+        res: &Result<reqwest::Response, reqwest_middleware::Error>,
+    ) -> Option<Retryable> {
+        match res {
+            Ok(response) if self.0.contains(&response.status().as_u16()) => {
+                Some(Retryable::Transient)
+            }
+            Ok(response) if response.status().is_success() => None,
+            Ok(_) => Some(Retryable::Fatal),
+            Err(error) => default_on_request_failure(error),
+        }
+    }
+}
+
+// The HTTP client of a load, the exporter sends its requests with it too.
+// Failed requests are retried here, one at a time, so a retry never
+// repeats batches which were already handed in.
+pub fn build_client(
+    db_config: &DatabaseConfiguration,
+    retry_policy: &RetryPolicy,
+    timeouts: &ClientTimeouts,
+) -> Result<ClientWithMiddleware, LoadError> {
+    let connection_error = |message: String| LoadError::new(ErrorKind::Connection, message);
+    let mut client_builder = timeouts.apply(reqwest::Client::builder());
+    if db_config.endpoints[0].starts_with("https://") {
+        client_builder = client_builder
+            .min_tls_version(reqwest::tls::Version::TLS_1_2)
+            .https_only(true)
+            .use_rustls_tls();
+        client_builder = match &db_config.tls_cert {
+            Some(cert_path) => {
+                let pem = std::fs::read(cert_path).map_err(|e| {
+                    connection_error(format!(
+                        "Could not read TLS certificate {}: {}",
+                        cert_path, e
+                    ))
+                })?;
+                let cert = reqwest::Certificate::from_pem(&pem).map_err(|e| {
+                    connection_error(format!("Invalid TLS certificate {}: {}", cert_path, e))
+                })?;
+                client_builder.add_root_certificate(cert)
+            }
+            None => client_builder.danger_accept_invalid_certs(true),
+        };
+    } else {
+        client_builder = client_builder.danger_accept_invalid_certs(true);
+    }
+    let client = client_builder
+        .build()
+        .map_err(|e| connection_error(format!("Could not build HTTP client: {}", e)))?;
+    Ok(ClientBuilder::new(client)
+        .with(retry_policy.middleware())
+        .build())
+}
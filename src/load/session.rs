@@ -0,0 +1,99 @@
+use crate::error::LoadError;
+use crate::load::retry::{build_client, RetryPolicy};
+use crate::load::timeout::ClientTimeouts;
+use arangors_graph_exporter::request::handle_arangodb_response_with_parsed_body;
+use arangors_graph_exporter::DatabaseConfiguration;
+use log::debug;
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+// ArangoDB expires JWTs after its session timeout (one hour by default),
+// so a token is renewed well before that.
+const TOKEN_LIFETIME: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Deserialize)]
+struct AuthResponse {
+    jwt: String,
+}
+
+// State reused by consecutive loads from the same database: the runtime
+// they run on, the HTTP client of their sources and a JWT, so username and
+// password are only checked once per token. Collection and AQL loads both
+// use them.
+pub struct Session {
+    db_config: DatabaseConfiguration,
+    runtime: Runtime,
+    client: ClientWithMiddleware,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl Session {
+    pub fn new(
+        db_config: DatabaseConfiguration,
+        retry_policy: &RetryPolicy,
+        timeouts: &ClientTimeouts,
+    ) -> Result<Self, LoadError> {
+        if db_config.endpoints.is_empty() {
+            return Err("no endpoints given".into());
+        }
+        let client = build_client(&db_config, retry_policy, timeouts)?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Could not create runtime: {}", e))?;
+        Ok(Session {
+            db_config,
+            runtime,
+            client,
+            token: Mutex::new(None),
+        })
+    }
+
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    pub fn client(&self) -> ClientWithMiddleware {
+        self.client.clone()
+    }
+
+    // The database configuration for the next load, authenticating with a
+    // JWT instead of username and password where possible.
+    pub async fn db_config(&self) -> Result<DatabaseConfiguration, LoadError> {
+        if !self.db_config.jwt_token.is_empty() || self.db_config.username.is_empty() {
+            return Ok(self.db_config.clone());
+        }
+        let cached = self.token.lock().unwrap().clone();
+        let jwt_token = match cached {
+            Some((token, created)) if created.elapsed() < TOKEN_LIFETIME => token,
+            _ => {
+                let token = self.authenticate().await?;
+                *self.token.lock().unwrap() = Some((token.clone(), Instant::now()));
+                token
+            }
+        };
+        Ok(DatabaseConfiguration {
+            jwt_token,
+            ..self.db_config.clone()
+        })
+    }
+
+    async fn authenticate(&self) -> Result<String, LoadError> {
+        debug!("Requesting JWT for user {}", self.db_config.username);
+        let url = self.db_config.endpoints[0].clone() + "/_open/auth";
+        let body = json!({
+            "username": self.db_config.username,
+            "password": self.db_config.password,
+        });
+        let resp = self.client.post(url).body(body.to_string()).send().await;
+        let auth = handle_arangodb_response_with_parsed_body::<AuthResponse>(resp, StatusCode::OK)
+            .await
+            .map_err(|e| LoadError::from(e).context("Could not authenticate"))?;
+        Ok(auth.jwt)
+    }
+}
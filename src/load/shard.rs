@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+// One shard per stream of a source. The batches of a stream come in one
+// after the other, so its shard is filled in batch order and workers on
+// different streams never wait for each other. Taking the shards in stream
+// order then makes the merged graph independent of how the streams
+// interleaved.
+#[derive(Default)]
+pub struct StreamShards<S> {
+    shards: Mutex<BTreeMap<usize, Arc<Mutex<S>>>>,
+}
+
+impl<S: Default> StreamShards<S> {
+    pub fn with_shard<R>(&self, stream: usize, f: impl FnOnce(&mut S) -> R) -> R {
+        // Only held to look up the shard, not while it is filled
+        let shard = self
+            .shards
+            .lock()
+            .unwrap()
+            .entry(stream)
+            .or_default()
+            .clone();
+        let mut shard = shard.lock().unwrap();
+        f(&mut shard)
+    }
+
+    // Empties the store, returning the shards in stream order
+    pub fn take(&self) -> Vec<S> {
+        std::mem::take(&mut *self.shards.lock().unwrap())
+            .into_values()
+            .map(|shard| std::mem::take(&mut *shard.lock().unwrap()))
+            .collect()
+    }
+}
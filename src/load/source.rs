@@ -0,0 +1,53 @@
+use crate::error::LoadError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+// Where a batch comes from. A source splits its data into streams, e.g. one
+// per collection or shard, and numbers the batches of every stream from 0.
+// The same request always yields the same ids for the same batches, so
+// merging in id order gives the same graph however the streams interleave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BatchId {
+    pub stream: usize,
+    pub position: u64,
+}
+
+impl BatchId {
+    pub fn new(stream: usize, position: u64) -> Self {
+        BatchId { stream, position }
+    }
+}
+
+// Called with (batch_id, vertex_ids, columns, field_names) for every batch of vertices
+pub type VertexBatchHandler = Arc<
+    dyn Fn(BatchId, &Vec<Vec<u8>>, &mut Vec<Vec<Value>>, &Vec<String>) -> Result<(), LoadError>
+        + Send
+        + Sync,
+>;
+
+// Called with (batch_id, from_ids, to_ids, columns, field_names) for every batch of edges
+pub type EdgeBatchHandler = Arc<
+    dyn Fn(
+            BatchId,
+            &Vec<Vec<u8>>,
+            &Vec<Vec<u8>>,
+            &mut Vec<Vec<Value>>,
+            &Vec<String>,
+        ) -> Result<(), LoadError>
+        + Send
+        + Sync,
+>;
+
+/// Anything that can stream vertex and edge batches into a `Graph`.
+///
+/// Handlers may be called concurrently from several threads, but the batches
+/// of one stream are handed in one after the other, in position order.
+/// Columns follow the exporter convention: with `load_all_*_attributes` each
+/// row holds the whole document as a single column, otherwise one column per
+/// field name.
+pub trait GraphSource {
+    async fn load_vertices(&self, handler: VertexBatchHandler) -> Result<(), LoadError>;
+
+    async fn load_edges(&self, handler: EdgeBatchHandler) -> Result<(), LoadError>;
+}
@@ -0,0 +1,34 @@
+use crate::error::LoadError;
+use std::time::Duration;
+
+// Timeouts of the HTTP client shared by the sources of a load, so they
+// apply to collection loads and AQL queries alike.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientTimeouts {
+    pub connect: Option<Duration>,
+    // For a single attempt, a retried request gets the full timeout again
+    pub request: Option<Duration>,
+}
+
+impl ClientTimeouts {
+    pub fn apply(&self, mut client_builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(connect) = self.connect {
+            client_builder = client_builder.connect_timeout(connect);
+        }
+        if let Some(request) = self.request {
+            client_builder = client_builder.timeout(request);
+        }
+        client_builder
+    }
+}
+
+// Resolves once `deadline` has passed, never without one
+pub async fn deadline_passed(deadline: Option<Duration>) -> LoadError {
+    match deadline {
+        Some(deadline) => {
+            tokio::time::sleep(deadline).await;
+            LoadError::timed_out(deadline)
+        }
+        None => std::future::pending().await,
+    }
+}
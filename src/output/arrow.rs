@@ -0,0 +1,139 @@
+use crate::error::LoadError;
+use crate::graph::{ArrowEdgeTable, ArrowVertexTable};
+use arrow_array::ffi::to_ffi;
+use arrow_array::ffi_stream::FFI_ArrowArrayStream;
+use arrow_array::types::Float64Type;
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, ListArray, NullArray, RecordBatch,
+    RecordBatchIterator, StringArray, StructArray, UInt64Array,
+};
+use arrow_schema::ffi::FFI_ArrowSchema;
+use arrow_schema::{ArrowError, Field, Schema};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::sync::Arc;
+
+// The type of a column is the narrowest one all its values fit:
+// integers, then numbers, booleans, strings and lists of numbers.
+// Anything else, e.g. objects or mixed values, is kept as JSON text.
+fn attribute_array(values: &[Value]) -> ArrayRef {
+    let present = || values.iter().filter(|v| !v.is_null());
+    if present().next().is_none() {
+        return Arc::new(NullArray::new(values.len()));
+    }
+    if present().all(|v| v.is_i64()) {
+        return Arc::new(values.iter().map(|v| v.as_i64()).collect::<Int64Array>());
+    }
+    if present().all(|v| v.is_number()) {
+        return Arc::new(values.iter().map(|v| v.as_f64()).collect::<Float64Array>());
+    }
+    if present().all(|v| v.is_boolean()) {
+        return Arc::new(values.iter().map(|v| v.as_bool()).collect::<BooleanArray>());
+    }
+    if present().all(|v| v.is_string()) {
+        return Arc::new(values.iter().map(|v| v.as_str()).collect::<StringArray>());
+    }
+    let is_vector = |v: &Value| {
+        v.as_array()
+            .is_some_and(|a| a.iter().all(|x| x.is_number()))
+    };
+    if present().all(is_vector) {
+        let vectors = values.iter().map(|v| {
+            v.as_array()
+                .map(|a| a.iter().map(|x| x.as_f64()).collect::<Vec<_>>())
+        });
+        return Arc::new(ListArray::from_iter_primitive::<Float64Type, _, _>(vectors));
+    }
+    let json = values.iter().map(|v| match v {
+        Value::Null => None,
+        v => Some(v.to_string()),
+    });
+    Arc::new(json.collect::<StringArray>())
+}
+
+fn record_batch(
+    mut columns: Vec<(String, ArrayRef)>,
+    attributes: &BTreeMap<String, Vec<Value>>,
+) -> Result<RecordBatch, LoadError> {
+    for (name, values) in attributes {
+        // Named like an index column, the attribute would be ambiguous
+        if columns.iter().any(|(column, _)| column == name) {
+            return Err(
+                format!("Attribute {} clashes with a column of the same name", name).into(),
+            );
+        }
+        columns.push((name.clone(), attribute_array(values)));
+    }
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, array)| Field::new(name, array.data_type().clone(), array.null_count() > 0))
+        .collect();
+    let arrays = columns.into_iter().map(|(_, array)| array).collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+        .map_err(|e| LoadError::from(format!("Could not build Arrow table: {}", e)))
+}
+
+// `_key` and `index`, then one column per attribute
+pub fn vertex_batch(table: &ArrowVertexTable) -> Result<RecordBatch, LoadError> {
+    let keys: ArrayRef = Arc::new(StringArray::from_iter_values(&table.keys));
+    let indices: ArrayRef = Arc::new(UInt64Array::from_iter_values(0..table.keys.len() as u64));
+    let columns = vec![("_key".to_string(), keys), ("index".to_string(), indices)];
+    record_batch(columns, &table.columns)
+}
+
+// `from_index` and `to_index` into the vertex tables, then one column per
+// attribute
+pub fn edge_batch(table: &ArrowEdgeTable) -> Result<RecordBatch, LoadError> {
+    let indices = |indices: &[usize]| -> ArrayRef {
+        Arc::new(UInt64Array::from_iter_values(
+            indices.iter().map(|&i| i as u64),
+        ))
+    };
+    let columns = vec![
+        ("from_index".to_string(), indices(&table.from_indices)),
+        ("to_index".to_string(), indices(&table.to_indices)),
+    ];
+    record_batch(columns, &table.columns)
+}
+
+fn capsule<'py, T: Send + 'static>(
+    py: Python<'py>,
+    value: T,
+    name: &str,
+) -> PyResult<&'py PyCapsule> {
+    let name = CString::new(name).unwrap();
+    PyCapsule::new(py, value, Some(name))
+}
+
+fn export_error(error: ArrowError) -> PyErr {
+    PyValueError::new_err(format!("Could not export Arrow table: {}", error))
+}
+
+// Capsules of the Arrow PyCapsule interface. Consumers move the structs out
+// of them, whatever is left is released once the capsule is dropped.
+pub fn schema_capsule<'py>(py: Python<'py>, batch: &RecordBatch) -> PyResult<&'py PyCapsule> {
+    let schema = FFI_ArrowSchema::try_from(batch.schema().as_ref()).map_err(export_error)?;
+    capsule(py, schema, "arrow_schema")
+}
+
+pub fn array_capsules<'py>(
+    py: Python<'py>,
+    batch: &RecordBatch,
+) -> PyResult<(&'py PyCapsule, &'py PyCapsule)> {
+    let (array, schema) =
+        to_ffi(&StructArray::from(batch.clone()).into_data()).map_err(export_error)?;
+    Ok((
+        capsule(py, schema, "arrow_schema")?,
+        capsule(py, array, "arrow_array")?,
+    ))
+}
+
+pub fn stream_capsule<'py>(py: Python<'py>, batch: &RecordBatch) -> PyResult<&'py PyCapsule> {
+    let reader = RecordBatchIterator::new(vec![Ok(batch.clone())], batch.schema());
+    let stream = FFI_ArrowArrayStream::new(Box::new(reader));
+    capsule(py, stream, "arrow_array_stream")
+}
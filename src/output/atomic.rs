@@ -0,0 +1,31 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+// Next to the file it replaces, `graph.bin` is written as `graph.bin.tmp`
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut temporary_path = OsString::from(path.as_os_str());
+    temporary_path.push(".tmp");
+    PathBuf::from(temporary_path)
+}
+
+// Writes to a temporary file which replaces `path` once it is complete and
+// on disk, so a failed write never leaves a partial file behind
+pub fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let temporary_path = temporary_path(path);
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&temporary_path)?);
+        write(&mut writer)?;
+        writer.into_inner()?.sync_all()?;
+        std::fs::rename(&temporary_path, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary_path);
+    }
+    result
+}
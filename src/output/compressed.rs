@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompressedFormat {
+    // Rows are the sources of the edges
+    Csr,
+    // Columns are the targets of the edges
+    Csc,
+}
+
+impl CompressedFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompressedFormat::Csr => "csr",
+            CompressedFormat::Csc => "csc",
+        }
+    }
+}
+
+// The edges of row (or column) `i` are `indices[indptr[i]..indptr[i + 1]]`,
+// edge `k` in this order is edge `permutation[k]` of the COO arrays
+pub struct CompressedAdjacency {
+    pub indptr: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub permutation: Vec<usize>,
+}
+
+// A counting sort by `major`, which keeps the edges of a row in COO order.
+// `major_len` is the number of rows, it must exceed every index in `major`.
+fn compress_by(major: &[usize], minor: &[usize], major_len: usize) -> CompressedAdjacency {
+    let mut indptr = vec![0; major_len + 1];
+    for &i in major {
+        indptr[i + 1] += 1;
+    }
+    for i in 0..major_len {
+        indptr[i + 1] += indptr[i];
+    }
+    let mut next = indptr[..major_len].to_vec();
+    let mut indices = vec![0; major.len()];
+    let mut permutation = vec![0; major.len()];
+    for (k, (&i, &j)) in major.iter().zip(minor).enumerate() {
+        let position = next[i];
+        indices[position] = j;
+        permutation[position] = k;
+        next[i] += 1;
+    }
+    CompressedAdjacency {
+        indptr,
+        indices,
+        permutation,
+    }
+}
+
+// `from_len` and `to_len` are the number of source and target vertices
+pub fn compress(
+    from: &[usize],
+    to: &[usize],
+    from_len: usize,
+    to_len: usize,
+    format: CompressedFormat,
+) -> CompressedAdjacency {
+    match format {
+        CompressedFormat::Csr => compress_by(from, to, from_len),
+        CompressedFormat::Csc => compress_by(to, from, to_len),
+    }
+}
+
+// Each requested format of the same COO arrays
+pub fn compress_all(
+    from: &[usize],
+    to: &[usize],
+    from_len: usize,
+    to_len: usize,
+    formats: &[CompressedFormat],
+) -> HashMap<CompressedFormat, CompressedAdjacency> {
+    formats
+        .iter()
+        .map(|&format| (format, compress(from, to, from_len, to_len, format)))
+        .collect()
+}
+
+// The compressed formats of every (edge, from, to) triple of a numpy result.
+// The number of vertices of a collection is one past its largest index.
+pub fn compress_coo_edge_map(
+    coo_edge_map: &HashMap<(String, String, String), Vec<Vec<usize>>>,
+    cols_to_inds_to_keys: &HashMap<String, HashMap<usize, String>>,
+    formats: &[CompressedFormat],
+) -> HashMap<(String, String, String), HashMap<CompressedFormat, CompressedAdjacency>> {
+    let vertex_count = |col_name: &str| {
+        cols_to_inds_to_keys
+            .get(col_name)
+            .and_then(|inds_to_keys| inds_to_keys.keys().max())
+            .map_or(0, |max_index| max_index + 1)
+    };
+    coo_edge_map
+        .iter()
+        .map(|(key_tup, coo)| {
+            let (_, from_col, to_col) = key_tup;
+            let compressed = compress_all(
+                &coo[0],
+                &coo[1],
+                vertex_count(from_col),
+                vertex_count(to_col),
+                formats,
+            );
+            (key_tup.clone(), compressed)
+        })
+        .collect()
+}
@@ -1,18 +1,20 @@
 use ndarray::{Array, Ix2};
-use numpy::ToPyArray;
+use numpy::{PyArray1, ToPyArray};
 use pyo3::types::{PyDict, PyList};
 use pyo3::{PyResult, Python};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde_json::{Map, Value};
 
-#[cfg(not(test))]
+use crate::graph::{DiGraphAdjMap, GraphAdjMap, MultiDiGraphAdjMap, MultiGraphAdjMap};
+use crate::load::inspect::{CollectionSummary, GraphInspection};
+use crate::output::compressed::{CompressedAdjacency, CompressedFormat};
+
 use pyo3::prelude::*;
 
-#[cfg(not(test))] // not(test) is needed to let us use `cargo test`
-pub fn construct_col_to_features(
-    input: HashMap<String, HashMap<String, Array<f64, Ix2>>>,
-    py: Python,
+pub fn construct_col_to_features<K: ToPyObject>(
+    input: HashMap<K, HashMap<String, Array<f64, Ix2>>>,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let dict = PyDict::new(py);
     input.iter().for_each(|(col_name, feature_map)| {
@@ -25,10 +27,9 @@ pub fn construct_col_to_features(
     Ok(dict)
 }
 
-#[cfg(not(test))]
 pub fn construct_coo_by_from_edge_to(
     input: HashMap<(String, String, String), Array<usize, Ix2>>,
-    py: Python,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let dict = PyDict::new(py);
     input
@@ -37,10 +38,9 @@ pub fn construct_coo_by_from_edge_to(
     Ok(dict)
 }
 
-#[cfg(not(test))]
 pub fn construct_cols_to_keys_to_inds(
     input: HashMap<String, HashMap<String, usize>>,
-    py: Python,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let dict = PyDict::new(py);
     input
@@ -49,10 +49,9 @@ pub fn construct_cols_to_keys_to_inds(
     Ok(dict)
 }
 
-#[cfg(not(test))]
 pub fn construct_cols_to_inds_to_keys(
     input: HashMap<String, HashMap<usize, String>>,
-    py: Python,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let dict = PyDict::new(py);
     input
@@ -61,10 +60,9 @@ pub fn construct_cols_to_inds_to_keys(
     Ok(dict)
 }
 
-#[cfg(not(test))]
 pub fn construct_vertex_id_to_index(
     input: HashMap<String, usize>,
-    py: Python,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
@@ -77,7 +75,7 @@ pub fn construct_vertex_id_to_index(
 
 pub fn construct_edge_value_dict(
     input: HashMap<String, Vec<f64>>,
-    py: Python,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
@@ -89,14 +87,13 @@ pub fn construct_edge_value_dict(
     Ok(pydict)
 }
 
-#[cfg(not(test))]
 /// {
 ///    "node/1": {property_key: property_value},
 ///    ...
 /// }
 pub fn construct_node_dict(
     input: HashMap<String, Map<String, Value>>,
-    py: Python,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
@@ -112,7 +109,6 @@ pub fn construct_node_dict(
     Ok(pydict)
 }
 
-#[cfg(not(test))]
 /// {
 ///     "node/1": {
 ///         "node/2": {property_key: property_value},
@@ -120,10 +116,7 @@ pub fn construct_node_dict(
 ///     },
 ///     ...
 /// }
-pub fn construct_graph_adj_dict(
-    input: HashMap<String, HashMap<String, Map<String, Value>>>,
-    py: Python,
-) -> PyResult<&PyDict> {
+pub fn construct_graph_adj_dict(input: GraphAdjMap, py: Python<'_>) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
     for (key, properties) in input.iter() {
@@ -142,7 +135,6 @@ pub fn construct_graph_adj_dict(
     Ok(pydict)
 }
 
-#[cfg(not(test))]
 /// {
 ///     "succ": {
 ///         "node/1": {
@@ -156,10 +148,7 @@ pub fn construct_graph_adj_dict(
 ///         ...
 ///     }
 /// }
-pub fn construct_digraph_adj_dict(
-    input: HashMap<String, HashMap<String, HashMap<String, Map<String, Value>>>>,
-    py: Python,
-) -> PyResult<&PyDict> {
+pub fn construct_digraph_adj_dict(input: DiGraphAdjMap, py: Python<'_>) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
     for (key, properties) in input.iter() {
@@ -182,7 +171,6 @@ pub fn construct_digraph_adj_dict(
     Ok(pydict)
 }
 
-#[cfg(not(test))]
 /// {
 ///     "node/1": {
 ///         "node/2": {
@@ -194,10 +182,7 @@ pub fn construct_digraph_adj_dict(
 ///     },
 ///     ...
 /// }
-pub fn construct_multigraph_adj_dict(
-    input: HashMap<String, HashMap<String, HashMap<usize, Map<String, Value>>>>,
-    py: Python,
-) -> PyResult<&PyDict> {
+pub fn construct_multigraph_adj_dict(input: MultiGraphAdjMap, py: Python<'_>) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
     for (key, properties) in input.iter() {
@@ -220,7 +205,6 @@ pub fn construct_multigraph_adj_dict(
     Ok(pydict)
 }
 
-#[cfg(not(test))]
 /// {
 ///     "succ": {
 ///         "node/1": {
@@ -239,8 +223,8 @@ pub fn construct_multigraph_adj_dict(
 ///     }
 /// }
 pub fn construct_multidigraph_adj_dict(
-    input: HashMap<String, HashMap<String, HashMap<String, HashMap<usize, Map<String, Value>>>>>,
-    py: Python,
+    input: MultiDiGraphAdjMap,
+    py: Python<'_>,
 ) -> PyResult<&PyDict> {
     let pydict = PyDict::new(py);
 
@@ -274,9 +258,65 @@ pub fn construct_multidigraph_adj_dict(
     Ok(pydict)
 }
 
-#[cfg(not(test))]
+/// {
+///     "vertex_collections": {
+///         "person": {
+///             "count": 34,
+///             "sampled": 34,
+///             "attributes": {
+///                 "features": {
+///                     "types": {"array": 34},
+///                     "array_lengths": [16],
+///                     "missing": 0,
+///                 },
+///                 ...
+///             },
+///         },
+///         ...
+///     },
+///     "edge_collections": {...},
+///     "edge_triples": [("knows", "person", "person"), ...]
+/// }
+pub fn construct_inspection(input: GraphInspection, py: Python<'_>) -> PyResult<&PyDict> {
+    let collections_dict = |collections: BTreeMap<String, CollectionSummary>| {
+        let pydict = PyDict::new(py);
+        for (name, summary) in collections {
+            let attributes_dict = PyDict::new(py);
+            for (attribute, attribute_summary) in summary.attributes {
+                let attribute_dict = PyDict::new(py);
+                attribute_dict.set_item("types", attribute_summary.types)?;
+                attribute_dict.set_item(
+                    "array_lengths",
+                    Vec::from_iter(attribute_summary.array_lengths),
+                )?;
+                attribute_dict.set_item("missing", attribute_summary.missing)?;
+                attributes_dict.set_item(attribute, attribute_dict)?;
+            }
+            let collection_dict = PyDict::new(py);
+            collection_dict.set_item("count", summary.count)?;
+            collection_dict.set_item("sampled", summary.sampled)?;
+            collection_dict.set_item("attributes", attributes_dict)?;
+            pydict.set_item(name, collection_dict)?;
+        }
+        Ok::<&PyDict, PyErr>(pydict)
+    };
+
+    let pydict = PyDict::new(py);
+    pydict.set_item(
+        "vertex_collections",
+        collections_dict(input.vertex_collections)?,
+    )?;
+    pydict.set_item(
+        "edge_collections",
+        collections_dict(input.edge_collections)?,
+    )?;
+    pydict.set_item("edge_triples", Vec::from_iter(input.edge_triples))?;
+
+    Ok(pydict)
+}
+
 // Construct a Python object from a serde_json Value
-fn construct_py_object(value: &Value, py: Python) -> PyResult<PyObject> {
+pub fn construct_py_object(value: &Value, py: Python<'_>) -> PyResult<PyObject> {
     match value {
         Value::Null => Ok(py.None()),
         Value::String(s) => Ok(s.to_object(py)),
@@ -306,3 +346,31 @@ fn construct_py_object(value: &Value, py: Python) -> PyResult<PyObject> {
         }
     }
 }
+
+// e.g. {'csr': (indptr, indices, permutation), ...}
+pub fn construct_compressed_adjacency(
+    input: HashMap<CompressedFormat, CompressedAdjacency>,
+    py: Python<'_>,
+) -> PyResult<&PyDict> {
+    let dict = PyDict::new(py);
+    for (format, adjacency) in input {
+        let arrays = (
+            PyArray1::from_vec(py, adjacency.indptr),
+            PyArray1::from_vec(py, adjacency.indices),
+            PyArray1::from_vec(py, adjacency.permutation),
+        );
+        dict.set_item(format.name(), arrays)?;
+    }
+    Ok(dict)
+}
+
+pub fn construct_compressed_by_from_edge_to(
+    input: HashMap<(String, String, String), HashMap<CompressedFormat, CompressedAdjacency>>,
+    py: Python<'_>,
+) -> PyResult<&PyDict> {
+    let dict = PyDict::new(py);
+    for (key_tup, compressed) in input {
+        dict.set_item(key_tup, construct_compressed_adjacency(compressed, py)?)?;
+    }
+    Ok(dict)
+}
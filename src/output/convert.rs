@@ -1,5 +1,6 @@
 use ndarray::{Array2, Axis};
 use std::collections::HashMap;
+use std::hash::Hash;
 
 pub fn convert_coo_edge_map(
     coo_edge_map: HashMap<(String, String, String), Vec<Vec<usize>>>,
@@ -12,9 +13,10 @@ pub fn convert_coo_edge_map(
         .collect()
 }
 
-pub fn convert_nested_features_map(
-    nested_features_map: HashMap<String, HashMap<String, Vec<Vec<f64>>>>,
-) -> HashMap<String, HashMap<String, Array2<f64>>> {
+// Keyed by vertex collection, or by (edge, from, to) triple for edge features
+pub fn convert_nested_features_map<K: Clone + Eq + Hash>(
+    nested_features_map: HashMap<K, HashMap<String, Vec<Vec<f64>>>>,
+) -> HashMap<K, HashMap<String, Array2<f64>>> {
     nested_features_map
         .iter()
         .map(|(col_name, features)| {
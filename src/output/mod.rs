@@ -1,2 +1,7 @@
+pub mod arrow;
+pub mod atomic;
+pub mod compressed;
 pub mod construct;
 pub mod convert;
+pub mod parquet;
+pub mod snapshot;
@@ -0,0 +1,537 @@
+use crate::error::LoadError;
+use crate::graph::{
+    fill_missing_edge_features, is_edge_feature_name, parse_edge_features, parse_vertex_features,
+    resolve_edge_indices, Graph, NumpyGraph, NumpyVertexShard,
+};
+use arrow_array::builder::{Float64Builder, ListBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, UInt64Type};
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+// Written last, a dataset without it is incomplete
+const SUCCESS_MARKER: &str = "_SUCCESS";
+// Edges are handed to the Parquet writer in batches of this many rows
+const EDGE_BATCH_SIZE: usize = 65536;
+
+fn parquet_error(path: &Path, error: impl std::fmt::Display) -> LoadError {
+    LoadError::from(format!(
+        "Could not access Parquet file {}: {}",
+        path.display(),
+        error
+    ))
+}
+
+fn feature_field(name: &str) -> Field {
+    let item = Field::new_list_field(DataType::Float64, true);
+    Field::new(name, DataType::List(Arc::new(item)), true)
+}
+
+fn edge_schema(feature_names: &[String]) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("from_index", DataType::UInt64, false),
+        Field::new("to_index", DataType::UInt64, false),
+    ];
+    fields.extend(feature_names.iter().map(|name| feature_field(name)));
+    Arc::new(Schema::new(fields))
+}
+
+// The numpy result as a directory of Parquet datasets, partitioned the way
+// Hive does it, so e.g. `pyarrow.dataset` reads the partitions as columns:
+//   vertices/collection=<name>/part-<n>.parquet
+//     `_key`, `index` and a list column per feature, null if a vertex lacks it
+//   edges/edge=<name>/from=<name>/to=<name>/part-<n>.parquet
+//     `from_index` and `to_index` into the vertex datasets and a list
+//     column per edge feature, null if an edge lacks it
+pub struct ParquetDataset {
+    path: PathBuf,
+    // Parts are numbered across the whole dataset in the order they are
+    // created, which is also the order they are read in
+    next_part: AtomicUsize,
+}
+
+impl ParquetDataset {
+    // The directory is created if missing, an existing one must be empty
+    pub fn create(path: &Path) -> Result<Self, LoadError> {
+        std::fs::create_dir_all(path).map_err(|e| parquet_error(path, e))?;
+        let mut entries = std::fs::read_dir(path).map_err(|e| parquet_error(path, e))?;
+        if entries.next().is_some() {
+            return Err(format!("Parquet output directory {} is not empty", path.display()).into());
+        }
+        Ok(ParquetDataset {
+            path: path.to_path_buf(),
+            next_part: AtomicUsize::new(0),
+        })
+    }
+
+    fn create_part(&self, directory: PathBuf, schema: SchemaRef) -> Result<PartWriter, LoadError> {
+        std::fs::create_dir_all(&directory).map_err(|e| parquet_error(&directory, e))?;
+        let part = self.next_part.fetch_add(1, Ordering::SeqCst);
+        let path = directory.join(format!("part-{:05}.parquet", part));
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let file = File::create(&path).map_err(|e| parquet_error(&path, e))?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(properties))
+            .map_err(|e| parquet_error(&path, e))?;
+        Ok(PartWriter {
+            path,
+            schema,
+            writer,
+        })
+    }
+
+    // `rows` are the features of the vertices with indices from
+    // `first_index` on, `feature_names` the columns of the collection
+    pub fn write_vertices(
+        &self,
+        collection: &str,
+        first_index: usize,
+        rows: &[(String, HashMap<String, Vec<f64>>)],
+        feature_names: &[String],
+    ) -> Result<(), LoadError> {
+        let mut fields = vec![
+            Field::new("_key", DataType::Utf8, false),
+            Field::new("index", DataType::UInt64, false),
+        ];
+        fields.extend(feature_names.iter().map(|name| feature_field(name)));
+        let keys = StringArray::from_iter_values(rows.iter().map(|(key, _)| key));
+        let indices = UInt64Array::from_iter_values(
+            (first_index..first_index + rows.len()).map(|i| i as u64),
+        );
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(keys), Arc::new(indices)];
+        for name in feature_names {
+            let mut builder = ListBuilder::new(Float64Builder::new());
+            for (_, features) in rows {
+                match features.get(name) {
+                    Some(feature) => builder.append_value(feature.iter().map(|&x| Some(x))),
+                    None => builder.append_null(),
+                }
+            }
+            columns.push(Arc::new(builder.finish()));
+        }
+
+        let directory = self
+            .path
+            .join("vertices")
+            .join(format!("collection={}", collection));
+        let mut part = self.create_part(directory, Arc::new(Schema::new(fields)))?;
+        part.write(columns)?;
+        part.close()
+    }
+
+    // `feature_names` are the columns of the edge collection
+    pub fn edge_writer(
+        &self,
+        key_tup: &(String, String, String),
+        feature_names: &[String],
+    ) -> Result<EdgePartWriter, LoadError> {
+        let (edge, from, to) = key_tup;
+        let directory = self
+            .path
+            .join("edges")
+            .join(format!("edge={}", edge))
+            .join(format!("from={}", from))
+            .join(format!("to={}", to));
+        Ok(EdgePartWriter {
+            part: self.create_part(directory, edge_schema(feature_names))?,
+            from_indices: vec![],
+            to_indices: vec![],
+            features: feature_names
+                .iter()
+                .map(|name| (name.clone(), ListBuilder::new(Float64Builder::new())))
+                .collect(),
+        })
+    }
+
+    pub fn mark_complete(&self) -> Result<(), LoadError> {
+        let path = self.path.join(SUCCESS_MARKER);
+        File::create(&path).map_err(|e| parquet_error(&path, e))?;
+        Ok(())
+    }
+}
+
+struct PartWriter {
+    path: PathBuf,
+    schema: SchemaRef,
+    writer: ArrowWriter<File>,
+}
+
+impl PartWriter {
+    fn write(&mut self, columns: Vec<ArrayRef>) -> Result<(), LoadError> {
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|e| parquet_error(&self.path, e))?;
+        self.writer
+            .write(&batch)
+            .map_err(|e| parquet_error(&self.path, e))
+    }
+
+    fn close(self) -> Result<(), LoadError> {
+        self.writer
+            .close()
+            .map_err(|e| parquet_error(&self.path, e))?;
+        Ok(())
+    }
+}
+
+// The edges of one (edge, from, to) triple resolved from one stream
+pub struct EdgePartWriter {
+    part: PartWriter,
+    from_indices: Vec<u64>,
+    to_indices: Vec<u64>,
+    features: Vec<(String, ListBuilder<Float64Builder>)>,
+}
+
+impl EdgePartWriter {
+    pub fn push(
+        &mut self,
+        from_index: usize,
+        to_index: usize,
+        features: &HashMap<String, Vec<f64>>,
+    ) -> Result<(), LoadError> {
+        self.from_indices.push(from_index as u64);
+        self.to_indices.push(to_index as u64);
+        for (name, builder) in self.features.iter_mut() {
+            match features.get(name) {
+                Some(feature) => builder.append_value(feature.iter().map(|&x| Some(x))),
+                None => builder.append_null(),
+            }
+        }
+        if self.from_indices.len() >= EDGE_BATCH_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), LoadError> {
+        let from_indices = UInt64Array::from(std::mem::take(&mut self.from_indices));
+        let to_indices = UInt64Array::from(std::mem::take(&mut self.to_indices));
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(from_indices), Arc::new(to_indices)];
+        for (_, builder) in self.features.iter_mut() {
+            columns.push(Arc::new(builder.finish()));
+        }
+        self.part.write(columns)
+    }
+
+    pub fn close(mut self) -> Result<(), LoadError> {
+        if !self.from_indices.is_empty() {
+            self.flush()?;
+        }
+        self.part.close()
+    }
+}
+
+// Writes what the numpy graph holds to a Parquet dataset instead, only the
+// indices of the keys are kept to resolve the edges. Vertex indices are only
+// known once all vertices are in, so vertices are written when the shards
+// are merged, edges while they are resolved.
+pub struct ParquetGraph {
+    index: NumpyGraph,
+    dataset: ParquetDataset,
+}
+
+// Vertices of one stream with their features, per collection in insertion
+// order
+pub type ParquetVertexShard = HashMap<String, Vec<(String, HashMap<String, Vec<f64>>)>>;
+
+// Edges of one stream, written as they are resolved
+pub type ParquetEdgeShard = HashMap<(String, String, String), EdgePartWriter>;
+
+impl ParquetGraph {
+    pub fn new(dataset: ParquetDataset) -> Arc<RwLock<ParquetGraph>> {
+        Arc::new(RwLock::new(ParquetGraph {
+            index: NumpyGraph::default(),
+            dataset,
+        }))
+    }
+}
+
+impl Graph for ParquetGraph {
+    type VertexShard = ParquetVertexShard;
+    type EdgeShard = ParquetEdgeShard;
+
+    fn insert_vertex(
+        &self,
+        shard: &mut ParquetVertexShard,
+        id: Vec<u8>, // cannot be empty
+        columns: Vec<Value>,
+        field_names: &[String],
+    ) -> anyhow::Result<()> {
+        if let Some((col_name, key, features)) = parse_vertex_features(id, &columns, field_names)? {
+            shard.entry(col_name).or_default().push((key, features));
+        }
+        Ok(())
+    }
+
+    fn merge_vertices(&mut self, shards: Vec<ParquetVertexShard>) -> anyhow::Result<()> {
+        // All parts of a collection have the same columns
+        let mut feature_names: HashMap<String, BTreeSet<String>> = HashMap::new();
+        for (col_name, rows) in shards.iter().flatten() {
+            let names = feature_names.entry(col_name.clone()).or_default();
+            names.extend(
+                rows.iter()
+                    .flat_map(|(_, features)| features.keys().cloned()),
+            );
+        }
+        for shard in shards {
+            let mut index_shard = NumpyVertexShard::default();
+            for (col_name, rows) in shard {
+                let first_index = self
+                    .index
+                    .cols_to_keys_to_inds
+                    .get(&col_name)
+                    .map_or(0, |keys_to_inds| keys_to_inds.len());
+                let names: Vec<String> = feature_names[&col_name].iter().cloned().collect();
+                self.dataset
+                    .write_vertices(&col_name, first_index, &rows, &names)?;
+                let keys = rows.into_iter().map(|(key, _)| key).collect();
+                index_shard.cols_to_keys.insert(col_name, keys);
+            }
+            self.index.merge_vertices(vec![index_shard])?;
+        }
+        Ok(())
+    }
+
+    fn insert_edge(
+        &self,
+        shard: &mut ParquetEdgeShard,
+        from_id: Vec<u8>,
+        to_id: Vec<u8>,
+        columns: Vec<Value>,
+        field_names: &[String],
+    ) -> anyhow::Result<()> {
+        let Some((key_tup, indices)) = resolve_edge_indices(
+            &self.index.cols_to_keys_to_inds,
+            from_id,
+            to_id,
+            &columns,
+            field_names,
+        )?
+        else {
+            return Ok(());
+        };
+
+        // Like the numpy graph, a triple is written even if none of its
+        // edges could be resolved
+        if !shard.contains_key(&key_tup) {
+            let feature_names: Vec<String> = field_names
+                .iter()
+                .filter(|name| is_edge_feature_name(name))
+                .cloned()
+                .collect();
+            let writer = self.dataset.edge_writer(&key_tup, &feature_names)?;
+            shard.insert(key_tup.clone(), writer);
+        }
+        if let Some((from_id, to_id)) = indices {
+            let features = parse_edge_features(&columns, field_names);
+            shard
+                .get_mut(&key_tup)
+                .unwrap()
+                .push(from_id, to_id, &features)?;
+        }
+        Ok(())
+    }
+
+    fn merge_edges(&mut self, shards: Vec<ParquetEdgeShard>) -> anyhow::Result<()> {
+        for writer in shards.into_iter().flat_map(|shard| shard.into_values()) {
+            writer.close()?;
+        }
+        self.dataset.mark_complete()?;
+        Ok(())
+    }
+}
+
+// The partitions in a directory, e.g. `collection=person`, by value
+fn partitions(directory: &Path, name: &str) -> Result<Vec<(String, PathBuf)>, LoadError> {
+    let prefix = format!("{}=", name);
+    let mut partitions = vec![];
+    let entries = std::fs::read_dir(directory).map_err(|e| parquet_error(directory, e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| parquet_error(directory, e))?.path();
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if let Some(value) = file_name.strip_prefix(&prefix) {
+            partitions.push((value.to_string(), path.clone()));
+        }
+    }
+    Ok(partitions)
+}
+
+// The batches of all parts of a partition, in the order they were written
+fn read_parts(directory: &Path) -> Result<Vec<RecordBatch>, LoadError> {
+    let mut parts = vec![];
+    let entries = std::fs::read_dir(directory).map_err(|e| parquet_error(directory, e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| parquet_error(directory, e))?.path();
+        if path.extension().is_some_and(|e| e == "parquet") {
+            parts.push(path);
+        }
+    }
+    parts.sort();
+    let mut batches = vec![];
+    for path in parts {
+        let file = File::open(&path).map_err(|e| parquet_error(&path, e))?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .and_then(|builder| builder.build())
+            .map_err(|e| parquet_error(&path, e))?;
+        for batch in reader {
+            batches.push(batch.map_err(|e| parquet_error(&path, e))?);
+        }
+    }
+    Ok(batches)
+}
+
+fn column<'a, T>(
+    batch: &'a RecordBatch,
+    name: &str,
+    directory: &Path,
+    cast: impl FnOnce(&'a ArrayRef) -> Option<T>,
+) -> Result<T, LoadError> {
+    batch
+        .column_by_name(name)
+        .and_then(cast)
+        .ok_or_else(|| parquet_error(directory, format!("no valid {} column", name)))
+}
+
+// Index, key and the features a vertex has
+type FeatureRow = (usize, String, Vec<(String, Vec<f64>)>);
+
+fn read_vertices(
+    graph: &mut NumpyGraph,
+    collection: String,
+    directory: &Path,
+) -> Result<(), LoadError> {
+    let mut rows: Vec<FeatureRow> = vec![];
+    for batch in read_parts(directory)? {
+        let keys = column(&batch, "_key", directory, |c| c.as_string_opt::<i32>())?;
+        let indices = column(&batch, "index", directory, |c| {
+            c.as_primitive_opt::<UInt64Type>()
+        })?;
+        let schema = batch.schema();
+        let mut features = vec![];
+        for field in schema.fields().iter().skip(2) {
+            let name = field.name();
+            let list = column(&batch, name, directory, |c| c.as_list_opt::<i32>())?;
+            features.push((name.clone(), list));
+        }
+        for i in 0..batch.num_rows() {
+            let mut row = vec![];
+            for (name, list) in &features {
+                if list.is_valid(i) {
+                    let values = list.value(i);
+                    let values = values.as_primitive::<Float64Type>();
+                    row.push((
+                        name.clone(),
+                        values.iter().map(|x| x.unwrap_or_default()).collect(),
+                    ));
+                }
+            }
+            rows.push((indices.value(i) as usize, keys.value(i).to_string(), row));
+        }
+    }
+
+    // Rows of a feature are in index order, vertices lacking it have none
+    rows.sort_by_key(|(index, _, _)| *index);
+    let keys_to_inds = graph
+        .cols_to_keys_to_inds
+        .entry(collection.clone())
+        .or_default();
+    let inds_to_keys = graph
+        .cols_to_inds_to_keys
+        .entry(collection.clone())
+        .or_default();
+    let col_to_features = graph.cols_to_features.entry(collection).or_default();
+    for (index, key, row) in rows {
+        keys_to_inds.insert(key.clone(), index);
+        inds_to_keys.insert(index, key);
+        for (name, feature) in row {
+            col_to_features.entry(name).or_default().push(feature);
+        }
+    }
+    Ok(())
+}
+
+fn read_edges(
+    graph: &mut NumpyGraph,
+    key_tup: (String, String, String),
+    directory: &Path,
+) -> Result<(), LoadError> {
+    let coo = graph
+        .coo_by_from_edge_to
+        .entry(key_tup.clone())
+        .or_insert_with(|| vec![vec![], vec![]]);
+    // Rows of edges lacking a feature stay empty until all parts are read
+    let mut features: HashMap<String, Vec<Vec<f64>>> = HashMap::new();
+    for batch in read_parts(directory)? {
+        let edge_offset = coo[0].len();
+        for (i, name) in ["from_index", "to_index"].into_iter().enumerate() {
+            let indices = column(&batch, name, directory, |c| {
+                c.as_primitive_opt::<UInt64Type>()
+            })?;
+            coo[i].extend(indices.values().iter().map(|&x| x as usize));
+        }
+        let schema = batch.schema();
+        for field in schema.fields().iter().skip(2) {
+            let name = field.name();
+            let list = column(&batch, name, directory, |c| c.as_list_opt::<i32>())?;
+            let rows = features.entry(name.clone()).or_default();
+            rows.resize(edge_offset, vec![]);
+            for i in 0..batch.num_rows() {
+                if list.is_valid(i) {
+                    let values = list.value(i);
+                    let values = values.as_primitive::<Float64Type>();
+                    rows.push(values.iter().map(|x| x.unwrap_or_default()).collect());
+                } else {
+                    rows.push(vec![]);
+                }
+            }
+        }
+    }
+
+    // Like in a load, a feature no edge has is left out
+    features.retain(|_, rows| rows.iter().any(|row| !row.is_empty()));
+    if features.is_empty() {
+        return Ok(());
+    }
+    fill_missing_edge_features(&key_tup, &mut features, coo[0].len())
+        .map_err(|e| parquet_error(directory, e))?;
+    graph.coo_features_by_from_edge_to.insert(key_tup, features);
+    Ok(())
+}
+
+// Rebuilds the numpy result a complete dataset was written from
+pub fn read_numpy_dataset(path: &Path) -> Result<NumpyGraph, LoadError> {
+    if !path.join(SUCCESS_MARKER).exists() {
+        return Err(format!("{} is not a complete Parquet export", path.display()).into());
+    }
+    let mut graph = NumpyGraph::default();
+    let vertices = path.join("vertices");
+    if vertices.exists() {
+        for (collection, directory) in partitions(&vertices, "collection")? {
+            read_vertices(&mut graph, collection, &directory)?;
+        }
+    }
+    let edges = path.join("edges");
+    if edges.exists() {
+        for (edge, edge_directory) in partitions(&edges, "edge")? {
+            for (from, from_directory) in partitions(&edge_directory, "from")? {
+                for (to, directory) in partitions(&from_directory, "to")? {
+                    read_edges(&mut graph, (edge.clone(), from.clone(), to), &directory)?;
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
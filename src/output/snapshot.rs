@@ -0,0 +1,93 @@
+use crate::error::LoadError;
+use crate::graph::Persist;
+use crate::output::atomic::write_atomically;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+// A snapshot starts with the magic bytes, the format version as a
+// little-endian u32 and the description of the graph. The data of the graph
+// as written by `Persist::save_data` follows.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"PHENOLRS";
+const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+fn io_error(path: &Path, error: impl std::fmt::Display) -> LoadError {
+    LoadError::from(format!(
+        "Could not access snapshot {}: {}",
+        path.display(),
+        error
+    ))
+}
+
+// An existing snapshot is only replaced by a complete one
+pub fn write_snapshot(path: &Path, graph: &dyn Persist) -> Result<(), LoadError> {
+    write_atomically(path, |writer| {
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_le_bytes())?;
+        rmp_serde::encode::write(writer, &graph.describe())?;
+        graph.save_data(writer)?;
+        Ok(())
+    })
+    .map_err(|e| io_error(path, e))
+}
+
+pub struct SnapshotReader {
+    path: PathBuf,
+    description: Value,
+    reader: BufReader<File>,
+}
+
+impl SnapshotReader {
+    // Reads up to the graph description, which tells how to create the
+    // graph the data is restored into
+    pub fn open(path: &Path) -> Result<Self, LoadError> {
+        let file = File::open(path).map_err(|e| io_error(path, e))?;
+        let mut reader = BufReader::new(file);
+        let mut magic = [0u8; 8];
+        let mut version = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .and_then(|_| reader.read_exact(&mut version))
+            .map_err(|e| io_error(path, e))?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(format!("{} is not a phenolrs snapshot", path.display()).into());
+        }
+        let version = u32::from_le_bytes(version);
+        if version != SNAPSHOT_FORMAT_VERSION {
+            return Err(format!(
+                "Snapshot {} has format version {}, only version {} can be read",
+                path.display(),
+                version,
+                SNAPSHOT_FORMAT_VERSION
+            )
+            .into());
+        }
+        let description: Value =
+            rmp_serde::decode::from_read(&mut reader).map_err(|e| io_error(path, e))?;
+        Ok(SnapshotReader {
+            path: path.to_path_buf(),
+            description,
+            reader,
+        })
+    }
+
+    pub fn description(&self) -> &Value {
+        &self.description
+    }
+
+    pub fn restore(mut self, graph: &mut dyn Persist) -> Result<(), LoadError> {
+        let kind = &self.description["graph"];
+        if graph.describe()["graph"] != *kind {
+            return Err(format!(
+                "Snapshot {} holds a {} graph",
+                self.path.display(),
+                kind.as_str().unwrap_or("different")
+            )
+            .into());
+        }
+        graph
+            .restore_data(&mut self.reader)
+            .map_err(|e| io_error(&self.path, e))
+    }
+}
@@ -0,0 +1,122 @@
+// `#[new]` in pyo3 0.20 expands to impls the lint flags, fixed in pyo3 0.21.
+// The Python classes with a constructor are kept here to allow it once.
+#![allow(non_local_definitions)]
+
+use crate::graph::NumpyGraph;
+use crate::input::load_request::{DataLoadRequest, NetworkXGraphConfig};
+use crate::input::parse::LocalDatabaseConfiguration;
+use crate::load;
+use crate::load::cancel::Cancellation;
+use crate::load::retry::RetryPolicy;
+use crate::{
+    load_graph, networkx_graph_factory, networkx_graph_to_python, numpy_graph_to_python,
+    python_error, NetworkXCompatible, PygCompatible,
+};
+use log::info;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Can be passed to a load to cancel it from another thread.
+/// Once cancelled, every load using the token is cancelled.
+#[pyclass]
+#[derive(Default)]
+pub struct CancellationToken(pub Cancellation);
+
+#[pymethods]
+impl CancellationToken {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// Loads several graphs from the same database. The runtime, an HTTP client
+/// and the authentication are set up once and reused by every load, whether
+/// it reads collections or runs AQL queries.
+/// Requests are the same as for the module functions, except that the
+/// database configuration is given to the session instead. `retry` is the
+/// retry policy of the session's client, see `load_config["retry"]`.
+#[pyclass]
+pub struct Session(load::session::Session);
+
+#[pymethods]
+impl Session {
+    #[new]
+    #[pyo3(signature = (database_config, retry = None))]
+    fn new(
+        py: Python,
+        database_config: LocalDatabaseConfiguration,
+        retry: Option<RetryPolicy>,
+    ) -> PyResult<Self> {
+        let _ = env_logger::try_init();
+        let retry_policy = retry.unwrap_or_default();
+        let LocalDatabaseConfiguration(db_config, timeouts) = database_config;
+        load::session::Session::new(db_config, &retry_policy, &timeouts)
+            .map(Session)
+            .map_err(|e| python_error(py, e))
+    }
+
+    #[pyo3(signature = (request, cancel_token = None))]
+    fn load_numpy<'a>(
+        &self,
+        py: Python<'a>,
+        request: &PyDict,
+        cancel_token: Option<PyRef<CancellationToken>>,
+    ) -> PyResult<PygCompatible<'a>> {
+        let request = self.prepare_request(py, request)?;
+
+        info!("Retrieving Numpy Graph in session...");
+        let start_time = std::time::Instant::now();
+        let graph = load_graph(py, request, NumpyGraph::new, self.0.runtime(), cancel_token)?;
+        info!("Retrieved. Took: {:?}", start_time.elapsed());
+
+        numpy_graph_to_python(py, graph)
+    }
+
+    #[pyo3(signature = (request, graph_config, cancel_token = None))]
+    fn load_networkx<'a>(
+        &self,
+        py: Python<'a>,
+        request: &PyDict,
+        graph_config: NetworkXGraphConfig,
+        cancel_token: Option<PyRef<CancellationToken>>,
+    ) -> PyResult<NetworkXCompatible<'a>> {
+        let request = self.prepare_request(py, request)?;
+        let graph_factory = networkx_graph_factory(&graph_config, &request);
+
+        info!("Retrieving NetworkX Graph in session...");
+        let start_time = std::time::Instant::now();
+        let graph = load_graph(py, request, graph_factory, self.0.runtime(), cancel_token)?;
+        info!("Retrieved. Took: {:?}", start_time.elapsed());
+
+        networkx_graph_to_python(py, graph, &graph_config)
+    }
+}
+
+impl Session {
+    fn prepare_request(&self, py: Python, request: &PyDict) -> PyResult<DataLoadRequest> {
+        if request.contains("database_config")? {
+            return Err(PyValueError::new_err(
+                "database_config is taken from the session",
+            ));
+        }
+        let mut request: DataLoadRequest = request.extract()?;
+        // Files are read without ever talking to the database
+        if request.file_source.is_none() {
+            request.db_config = py
+                .allow_threads(|| self.0.runtime().block_on(self.0.db_config()))
+                .map_err(|e| python_error(py, e))?;
+            request.client = Some(self.0.client());
+        }
+        Ok(request)
+    }
+}